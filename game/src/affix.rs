@@ -0,0 +1,405 @@
+//! Elite affix system - randomized enemy modifiers with visual tints and nameplates
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{GameState, GameEntity};
+use crate::enemy::{spawn_enemy, Enemy, EnemyType};
+use crate::fx::ParticleManager;
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::simulation::{GameplaySet, InterpolatedTransform};
+
+pub struct AffixPlugin;
+
+impl Plugin for AffixPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate, (
+                apply_hasted_system,
+                shield_regen_system,
+                teleport_system,
+            ).chain()
+             .in_set(GameplaySet::Affixes)
+             .run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, affix_death_system
+                .after(crate::enemy::projectile_collision_system)
+                .before(crate::enemy::enemy_health_system)
+                .in_set(GameplaySet::Enemies)
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                setup_affix_visuals_system,
+                update_nameplates_system,
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AffixType {
+    Shielded,
+    Splitting,
+    Vampiric,
+    Hasted,
+    Teleporting,
+    Explosive,
+    Reflective,
+    Freezing,
+    Corrosive,
+}
+
+impl AffixType {
+    pub const ALL: [AffixType; 9] = [
+        AffixType::Shielded,
+        AffixType::Splitting,
+        AffixType::Vampiric,
+        AffixType::Hasted,
+        AffixType::Teleporting,
+        AffixType::Explosive,
+        AffixType::Reflective,
+        AffixType::Freezing,
+        AffixType::Corrosive,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AffixType::Shielded => "Shielded",
+            AffixType::Splitting => "Splitting",
+            AffixType::Vampiric => "Vampiric",
+            AffixType::Hasted => "Hasted",
+            AffixType::Teleporting => "Teleporting",
+            AffixType::Explosive => "Explosive",
+            AffixType::Reflective => "Reflective",
+            AffixType::Freezing => "Freezing",
+            AffixType::Corrosive => "Corrosive",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            AffixType::Shielded => Color::CYAN,
+            AffixType::Splitting => Color::rgb(0.6, 1.0, 0.3),
+            AffixType::Vampiric => Color::CRIMSON,
+            AffixType::Hasted => Color::YELLOW,
+            AffixType::Teleporting => Color::PURPLE,
+            AffixType::Explosive => Color::ORANGE_RED,
+            AffixType::Reflective => Color::SILVER,
+            AffixType::Freezing => Color::rgb(0.6, 0.85, 1.0),
+            AffixType::Corrosive => Color::LIME_GREEN,
+        }
+    }
+}
+
+// Marker listing every affix on an enemy, used for the tint and nameplate
+#[derive(Component)]
+pub struct EliteAffixes {
+    pub affixes: Vec<AffixType>,
+}
+
+// Affix components - each one can be attached to any enemy on its own
+#[derive(Component)]
+pub struct Shielded {
+    pub shield: f32,
+    pub max_shield: f32,
+    pub regen_rate: f32,
+    pub regen_delay: f32,
+    pub last_hit_time: f32,
+}
+
+#[derive(Component)]
+pub struct Splitting {
+    pub count: u32,
+}
+
+#[derive(Component)]
+pub struct Vampiric {
+    pub heal_fraction: f32,
+}
+
+#[derive(Component)]
+pub struct Hasted {
+    pub speed_multiplier: f32,
+}
+
+#[derive(Component)]
+pub struct Teleporting {
+    pub interval: f32,
+    pub timer: f32,
+    pub range: f32,
+}
+
+#[derive(Component)]
+pub struct Explosive {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Component)]
+pub struct Reflective {
+    pub chance: f32,
+}
+
+// Shots that freeze the player in place for a moment
+#[derive(Component)]
+pub struct Freezing;
+
+// Shots that eat through armour, leaving the player vulnerable to further hits
+#[derive(Component)]
+pub struct Corrosive;
+
+#[derive(Component)]
+pub struct AffixNameplate {
+    pub target: Entity,
+}
+
+impl Shielded {
+    pub fn new(max_shield: f32) -> Self {
+        Self {
+            shield: max_shield,
+            max_shield,
+            regen_rate: 15.0,
+            regen_delay: 3.0,
+            last_hit_time: 0.0,
+        }
+    }
+
+    /// Soaks up as much of `damage` as the shield can and returns what is left over for health
+    pub fn absorb(&mut self, damage: f32, current_time: f32) -> f32 {
+        self.last_hit_time = current_time;
+        let absorbed = damage.min(self.shield);
+        self.shield -= absorbed;
+        damage - absorbed
+    }
+}
+
+pub fn roll_affixes(rng: &mut impl Rng, min: usize, max: usize) -> Vec<AffixType> {
+    use rand::seq::SliceRandom;
+
+    let count = rng.gen_range(min..=max).min(AffixType::ALL.len());
+    AffixType::ALL
+        .choose_multiple(rng, count)
+        .copied()
+        .collect()
+}
+
+/// Inserts the affix components for `affixes` on an already spawned enemy
+pub fn apply_affixes(
+    commands: &mut Commands,
+    entity: Entity,
+    enemy: &Enemy,
+    affixes: &[AffixType],
+) {
+    if affixes.is_empty() {
+        return;
+    }
+
+    for affix in affixes {
+        match affix {
+            AffixType::Shielded => {
+                commands.entity(entity).insert(Shielded::new(enemy.max_health * 0.5));
+            }
+            AffixType::Splitting => {
+                commands.entity(entity).insert(Splitting { count: 3 });
+            }
+            AffixType::Vampiric => {
+                commands.entity(entity).insert(Vampiric { heal_fraction: 0.5 });
+            }
+            AffixType::Hasted => {
+                commands.entity(entity).insert(Hasted { speed_multiplier: 1.5 });
+            }
+            AffixType::Teleporting => {
+                commands.entity(entity).insert(Teleporting {
+                    interval: 4.0,
+                    timer: 0.0,
+                    range: 120.0,
+                });
+            }
+            AffixType::Explosive => {
+                commands.entity(entity).insert(Explosive {
+                    radius: 80.0,
+                    damage: enemy.damage,
+                });
+            }
+            AffixType::Reflective => {
+                commands.entity(entity).insert(Reflective { chance: 0.25 });
+            }
+            AffixType::Freezing => {
+                commands.entity(entity).insert(Freezing);
+            }
+            AffixType::Corrosive => {
+                commands.entity(entity).insert(Corrosive);
+            }
+        }
+    }
+
+    commands.entity(entity).insert(EliteAffixes { affixes: affixes.to_vec() });
+}
+
+// Systems
+fn setup_affix_visuals_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<(Entity, &EliteAffixes), Added<EliteAffixes>>,
+) {
+    for (entity, elite) in query.iter() {
+        // Blend the affix colours into a translucent aura around the model
+        let tint = elite.affixes.iter()
+            .fold(Vec4::ZERO, |acc, affix| acc + Vec4::from(affix.color().as_rgba_f32()))
+            / elite.affixes.len() as f32;
+        let aura = commands.spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::UVSphere { radius: 1.2, ..default() })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(tint.x, tint.y, tint.z, 0.35),
+                emissive: Color::rgb(tint.x, tint.y, tint.z),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            ..default()
+        }).id();
+        commands.entity(entity).add_child(aura);
+
+        let label = elite.affixes.iter().map(|affix| affix.name()).collect::<Vec<_>>().join(" ");
+        commands.spawn((
+            TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                    font_size: 14.0,
+                    color: Color::rgb(tint.x, tint.y, tint.z),
+                },
+            ).with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+            AffixNameplate { target: entity },
+            GameEntity,
+            Name::new("AffixNameplate"),
+        ));
+    }
+}
+
+fn apply_hasted_system(
+    mut query: Query<(&mut Enemy, &Hasted), Added<Hasted>>,
+) {
+    for (mut enemy, hasted) in query.iter_mut() {
+        enemy.speed *= hasted.speed_multiplier;
+    }
+}
+
+fn shield_regen_system(
+    time: Res<Time>,
+    mut query: Query<&mut Shielded>,
+) {
+    let current_time = time.elapsed_seconds();
+
+    for mut shielded in query.iter_mut() {
+        if shielded.shield < shielded.max_shield
+            && current_time - shielded.last_hit_time >= shielded.regen_delay {
+            shielded.shield = (shielded.shield + shielded.regen_rate * time.delta_seconds())
+                .min(shielded.max_shield);
+        }
+    }
+}
+
+fn teleport_system(
+    time: Res<Time>,
+    mut game_rng: ResMut<GameRng>,
+    mut enemy_query: Query<(&mut Transform, &mut Teleporting, Option<&mut InterpolatedTransform>), With<Enemy>>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+) {
+    let rng = game_rng.combat();
+
+    if let Ok(player_transform) = player_query.get_single() {
+        for (mut transform, mut teleporting, interpolated) in enemy_query.iter_mut() {
+            teleporting.timer += time.delta_seconds();
+            if teleporting.timer < teleporting.interval {
+                continue;
+            }
+            teleporting.timer = 0.0;
+
+            // Blink to a random point near the player, staying on the ground plane
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let distance = rng.gen_range(teleporting.range * 0.5..teleporting.range);
+            transform.translation = Vec3::new(
+                player_transform.translation.x + angle.cos() * distance,
+                transform.translation.y,
+                player_transform.translation.z + angle.sin() * distance,
+            );
+            // Blink rather than slide across the arena between ticks
+            if let Some(mut interpolated) = interpolated {
+                interpolated.snap(*transform);
+            }
+        }
+    }
+}
+
+fn affix_death_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut particle_manager: ResMut<ParticleManager>,
+    mut game_rng: ResMut<GameRng>,
+    enemy_query: Query<(&Transform, &Enemy, Option<&Splitting>, Option<&Explosive>)>,
+    mut player_query: Query<(&Transform, &mut Player), Without<Enemy>>,
+) {
+
+    for (transform, enemy, splitting, explosive) in enemy_query.iter() {
+        if !enemy.is_dead() {
+            continue;
+        }
+
+        if let Some(splitting) = splitting {
+            for _ in 0..splitting.count {
+                let rng = game_rng.spawns();
+                let offset = Vec3::new(rng.gen_range(-30.0..30.0), 0.0, rng.gen_range(-30.0..30.0));
+                spawn_enemy(
+                    &mut commands,
+                    &asset_server,
+                    rng,
+                    EnemyType::Swarm,
+                    transform.translation + offset,
+                );
+            }
+        }
+
+        if let Some(explosive) = explosive {
+            particle_manager.spawn_explosion(&mut commands, game_rng.cosmetics(), transform.translation, 30);
+
+            if let Ok((player_transform, mut player)) = player_query.get_single_mut() {
+                if player_transform.translation.distance(transform.translation) <= explosive.radius {
+                    player.take_damage(explosive.damage);
+                }
+            }
+        }
+    }
+}
+
+fn update_nameplates_system(
+    mut commands: Commands,
+    camera_query: Query<(&Camera, &GlobalTransform), With<crate::MainCamera>>,
+    target_query: Query<&GlobalTransform, With<EliteAffixes>>,
+    mut nameplate_query: Query<(Entity, &AffixNameplate, &mut Style, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    for (entity, nameplate, mut style, mut visibility) in nameplate_query.iter_mut() {
+        let Ok(target_transform) = target_query.get(nameplate.target) else {
+            // Owner is gone, so is its nameplate
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        match camera.world_to_viewport(camera_transform, target_transform.translation()) {
+            Some(screen_pos) => {
+                style.left = Val::Px(screen_pos.x - 30.0);
+                style.top = Val::Px(screen_pos.y - 40.0);
+                *visibility = Visibility::Inherited;
+            }
+            None => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
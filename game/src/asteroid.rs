@@ -3,6 +3,8 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use rand::Rng;
 use crate::{GameState, GameEntity};
+use crate::rng::GameRng;
+use crate::simulation::{GameplaySet, InterpolatedTransform};
 
 pub struct AsteroidPlugin;
 
@@ -10,9 +12,10 @@ impl Plugin for AsteroidPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(OnEnter(GameState::Playing), spawn_asteroids)
-            .add_systems(Update, (
+            .add_systems(FixedUpdate, (
                 asteroid_system,
-            ).run_if(in_state(GameState::Playing)));
+            ).in_set(GameplaySet::Environment)
+             .run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -34,8 +37,15 @@ impl Asteroid {
 fn spawn_asteroids(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut game_rng: ResMut<GameRng>,
+    existing: Query<(), With<Asteroid>>,
 ) {
-    let mut rng = rand::thread_rng();
+    // Only lay out the field once per run, not again when resuming from pause or a level-up
+    if !existing.is_empty() {
+        return;
+    }
+    
+    let rng = game_rng.spawns();
     
     // Spawn asteroids in a scattered pattern around the arena
     for _ in 0..20 {
@@ -68,6 +78,7 @@ fn spawn_asteroids(
             // Lock Y movement to keep asteroids on the same plane
             LockedAxes::TRANSLATION_LOCKED_Y | LockedAxes::ROTATION_LOCKED_X | LockedAxes::ROTATION_LOCKED_Z,
             Asteroid::new(size),
+            InterpolatedTransform::default(),
             GameEntity,
             Name::new(format!("Asteroid_{}", size)),
         ));
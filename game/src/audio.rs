@@ -1,6 +1,9 @@
 //! Audio system - handles sound effects and music
+use bevy::asset::io::file::FileAssetReader;
+use bevy::audio::Volume;
 use bevy::prelude::*;
 use crate::GameState;
+use crate::wave::AIDirector;
 
 pub struct AudioPlugin;
 
@@ -21,6 +24,8 @@ pub struct AudioManager {
     pub sfx_volume: f32,
     pub music_volume: f32,
     pub current_music: Option<Handle<AudioSource>>,
+    pub current_track: Option<&'static str>,
+    music_entity: Option<Entity>,
     pub loaded_sounds: std::collections::HashMap<String, Handle<AudioSource>>,
 }
 
@@ -38,14 +43,16 @@ impl AudioManager {
             sfx_volume: 0.8,
             music_volume: 0.6,
             current_music: None,
+            current_track: None,
+            music_entity: None,
             loaded_sounds: std::collections::HashMap::new(),
         }
     }
     
     pub fn play_sound_effect(
         &self,
-        commands: &mut Commands,
-        asset_server: &Res<AssetServer>,
+        _commands: &mut Commands,
+        _asset_server: &Res<AssetServer>,
         sound_name: &str,
         volume: f32,
     ) {
@@ -61,28 +68,40 @@ impl AudioManager {
         // });
     }
     
+    /// Loops `assets/audio/music/<music_name>.ogg` in place of whatever was playing, or just stops
+    /// the music if that file isn't there
     pub fn play_music(
         &mut self,
         commands: &mut Commands,
         asset_server: &Res<AssetServer>,
         music_name: &str,
     ) {
+        self.stop_music(commands);
+        
+        // No tracks ship yet, and loading one that's missing only gets an error logged every switch
+        let path = format!("audio/music/{}.ogg", music_name);
+        if !FileAssetReader::get_base_path().join("assets").join(&path).exists() {
+            info!("No music file for {}, skipping playback", music_name);
+            return;
+        }
         info!("Playing music: {}", music_name);
         
-        // TODO: Implement actual music playback when audio assets are available
-        // let music_handle = asset_server.load(format!("audio/music/{}.ogg", music_name));
-        // self.current_music = Some(music_handle.clone());
-        // commands.spawn(AudioBundle {
-        //     source: music_handle,
-        //     settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(self.music_volume * self.master_volume)),
-        // });
+        let music_handle = asset_server.load(path);
+        self.current_music = Some(music_handle.clone());
+        let entity = commands.spawn(AudioBundle {
+            source: music_handle,
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(self.music_volume * self.master_volume)),
+        }).id();
+        self.music_entity = Some(entity);
     }
     
     pub fn stop_music(&mut self, commands: &mut Commands) {
         if self.current_music.is_some() {
             info!("Stopping current music");
             self.current_music = None;
-            // TODO: Implement actual music stopping
+        }
+        if let Some(entity) = self.music_entity.take() {
+            commands.entity(entity).despawn_recursive();
         }
     }
     
@@ -140,7 +159,22 @@ pub enum MusicTrack {
     GameOver,
 }
 
+// The intense track comes in above this director intensity and only drops back below the exit
+// threshold, so intensity hovering around one value doesn't flip the music back and forth
+pub const INTENSE_MUSIC_ENTER: f32 = 1.2;
+pub const INTENSE_MUSIC_EXIT: f32 = 1.05;
+
 impl MusicTrack {
+    /// The gameplay track for `intensity`, given whether the intense one is already playing
+    pub fn for_intensity(intensity: f32, intense_playing: bool) -> Self {
+        let threshold = if intense_playing { INTENSE_MUSIC_EXIT } else { INTENSE_MUSIC_ENTER };
+        if intensity >= threshold {
+            MusicTrack::GameplayIntense
+        } else {
+            MusicTrack::GameplayAmbient
+        }
+    }
+    
     pub fn as_str(&self) -> &'static str {
         match self {
             MusicTrack::MainMenu => "main_menu",
@@ -157,17 +191,21 @@ fn setup_audio(mut audio_manager: ResMut<AudioManager>) {
 }
 
 fn update_audio_system(
-    // For now, this system is mostly placeholder
-    // In the future, it could handle 3D audio positioning, dynamic music switching, etc.
-    audio_manager: Res<AudioManager>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    director: Res<AIDirector>,
+    mut audio_manager: ResMut<AudioManager>,
 ) {
     // TODO: Implement dynamic audio updates
     // - Update 3D audio positions based on player/camera position
-    // - Handle music transitions based on game state
     // - Manage audio memory usage
     
-    // Placeholder for now
-    if audio_manager.is_changed() {
-        // React to audio manager changes
+    // Switch music with the director's intensity
+    let intense_playing = audio_manager.current_track == Some(MusicTrack::GameplayIntense.as_str());
+    let track = MusicTrack::for_intensity(director.intensity, intense_playing);
+    
+    if audio_manager.current_track != Some(track.as_str()) {
+        audio_manager.play_music(&mut commands, &asset_server, track.as_str());
+        audio_manager.current_track = Some(track.as_str());
     }
 }
@@ -0,0 +1,390 @@
+//! Void Survivor - headless balance simulator
+//!
+//! Plays seeded wave runs with a scripted bot and no window or renderer, one fixed tick per
+//! update, so a run takes a fraction of its game time. Every wave a bot reaches becomes a CSV row
+//! (time to clear, damage taken, deaths, level reached) for tuning `EnemyConfig` and `WaveConfig`.
+//!
+//! Run from the repo root so `config/` resolves:
+//!   cargo run --release --bin void_survivor_sim -- --runs 1000 --seed 1 --out balance.csv
+//!
+//! Options: `--runs <n>` runs to play (100), `--seed <n>` seed of the first run, each later run
+//! adds one (1), `--waves <n>` stop a run after clearing this wave (20), `--max-minutes <n>` give
+//! up on a run after this much game time (30), `--threads <n>` runs played in parallel (all
+//! cores), `--fixed-script` turn the AI director off, `--out <file>` write CSV there instead of
+//! stdout.
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use void_survivor::{GameData, GameMode, GameState, VoidSurvivorPlugin};
+use void_survivor::config::{GameConfig, WaveConfig};
+use void_survivor::enemy::{Enemy, EnemyProjectile};
+use void_survivor::loot::Loot;
+use void_survivor::player::{capture_player_aim_system, Player, PlayerAim, PlayerMovement};
+use void_survivor::rng::GameRng;
+use void_survivor::upgrade::UpgradeManager;
+use void_survivor::wave::{WaveCompleteEvent, WaveManager};
+
+// Bot tuning
+const DANGER_RADIUS: f32 = 220.0; // Enemies closer than this push the bot away
+const DODGE_RADIUS: f32 = 120.0; // Enemy shots closer than this push twice as hard
+const LOOT_RADIUS: f32 = 300.0; // Collect pickups within this range when nothing is close
+const SEEK_DISTANCE: f32 = 350.0; // Close in when the nearest enemy is further than this
+const TURN_DEADZONE: f32 = 0.1; // Radians of heading error to ignore
+const THRUST_CONE: f32 = 0.6; // Only thrust when facing within this many radians of the goal
+
+const CSV_HEADER: &str = "run,seed,wave,cleared,time_to_clear,damage_taken,deaths,level,score";
+
+struct SimOptions {
+    runs: u32,
+    seed: u64,
+    waves: u32,
+    max_minutes: f32,
+    threads: usize,
+    fixed_script: bool,
+    out: Option<String>,
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        Self {
+            runs: 100,
+            seed: 1,
+            waves: 20,
+            max_minutes: 30.0,
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            fixed_script: false,
+            out: None,
+        }
+    }
+}
+
+impl SimOptions {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{} expects a value", name));
+            match arg.as_str() {
+                "--runs" => options.runs = parse(&arg, value(&arg)?)?,
+                "--seed" => options.seed = parse(&arg, value(&arg)?)?,
+                "--waves" => options.waves = parse(&arg, value(&arg)?)?,
+                "--max-minutes" => options.max_minutes = parse(&arg, value(&arg)?)?,
+                "--threads" => options.threads = parse::<usize>(&arg, value(&arg)?)?.max(1),
+                "--fixed-script" => options.fixed_script = true,
+                "--out" => options.out = Some(value(&arg)?),
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: String) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} expects a number, got {}", name, value))
+}
+
+// One CSV row - how a run got on in one wave
+#[derive(Clone, Debug)]
+struct WaveRow {
+    run: u32,
+    seed: u64,
+    wave: u32,
+    cleared: bool,
+    time_to_clear: f32,
+    damage_taken: f32,
+    deaths: u32,
+    level: u32,
+    score: u32,
+}
+
+// Per-run bookkeeping, read back out of the world when the run ends
+#[derive(Resource)]
+struct SimRun {
+    run: u32,
+    seed: u64,
+    target_wave: u32,
+    wave_started_at: Option<f32>,
+    rows: Vec<WaveRow>,
+    finished: bool,
+}
+
+impl SimRun {
+    // A run that has finished on a wave it didn't clear ended with the bot's death
+    fn record(&mut self, wave: u32, cleared: bool, time_to_clear: f32, damage_taken: f32, level: u32, score: u32) {
+        self.rows.push(WaveRow {
+            run: self.run,
+            seed: self.seed,
+            wave,
+            cleared,
+            time_to_clear,
+            damage_taken,
+            deaths: u32::from(!cleared && self.finished),
+            level,
+            score,
+        });
+    }
+}
+
+fn main() {
+    let options = match SimOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("void_survivor_sim: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    let started = Instant::now();
+    let threads = options.threads.min(options.runs.max(1) as usize);
+    let mut rows: Vec<WaveRow> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                let options = &options;
+                scope.spawn(move || {
+                    (0..options.runs)
+                        .filter(|run| *run as usize % threads == worker)
+                        .flat_map(|run| play_run(run, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    });
+    rows.sort_by_key(|row| (row.run, row.wave));
+
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for row in &rows {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{:.2},{:.1},{},{},{}",
+            row.run, row.seed, row.wave, row.cleared, row.time_to_clear, row.damage_taken, row.deaths, row.level, row.score,
+        );
+    }
+
+    match &options.out {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, csv) {
+                eprintln!("void_survivor_sim: could not write {} ({})", path, err);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", csv),
+    }
+
+    let deaths: u32 = rows.iter().map(|row| row.deaths).sum();
+    eprintln!(
+        "Simulated {} runs ({} waves, {} deaths) in {:.1}s",
+        options.runs,
+        rows.len(),
+        deaths,
+        started.elapsed().as_secs_f32(),
+    );
+}
+
+/// Plays one seeded run to the target wave, the bot's death or the time limit
+fn play_run(run: u32, options: &SimOptions) -> Vec<WaveRow> {
+    let seed = options.seed.wrapping_add(run as u64);
+    let mut app = build_sim_app(run, seed, options);
+
+    // Startup sets the tick rate, then every update advances exactly one tick
+    app.update();
+    let timestep = app.world.resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+    let max_ticks = (options.max_minutes * 60.0 / timestep.as_secs_f32()) as u64;
+    for _ in 0..max_ticks {
+        app.update();
+        if app.world.resource::<SimRun>().finished {
+            break;
+        }
+    }
+
+    let mut sim_run = app.world.remove_resource::<SimRun>().expect("SimRun is inserted at build");
+    if !sim_run.finished {
+        // Out of time - log how far the unfinished wave got
+        let wave_manager = app.world.resource::<WaveManager>();
+        let (wave, damage_taken) = (wave_manager.current_wave, wave_manager.wave_damage_taken);
+        let elapsed = app.world.resource::<Time>().elapsed_seconds();
+        let level = player_level(&mut app.world);
+        let score = app.world.resource::<GameData>().score;
+        let time = sim_run.wave_started_at.map_or(0.0, |start| elapsed - start);
+        sim_run.record(wave, false, time, damage_taken, level, score);
+    }
+    sim_run.rows
+}
+
+fn build_sim_app(run: u32, seed: u64, options: &SimOptions) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(VoidSurvivorPlugin::headless())
+        .insert_resource(GameMode::Waves)
+        .insert_resource(GameRng::new(seed))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO))
+        .insert_resource(SimRun {
+            run,
+            seed,
+            target_wave: options.waves,
+            wave_started_at: None,
+            rows: Vec::new(),
+            finished: false,
+        })
+        .add_systems(PreUpdate, bot_input_system.after(capture_player_aim_system))
+        .add_systems(Update, track_waves_system.run_if(in_state(GameState::Playing)));
+
+    if options.fixed_script {
+        app.world.resource_mut::<WaveConfig>().director_enabled = false;
+    }
+
+    // Same seed, same result: run systems in one fixed order rather than whichever thread wins
+    for (_, schedule) in app.world.resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+
+    app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+    app
+}
+
+fn player_level(world: &mut World) -> u32 {
+    world.query::<&Player>().get_single(world).map_or(1, |player| player.level)
+}
+
+// Systems
+#[allow(clippy::too_many_arguments)]
+fn bot_input_system(
+    config: Res<GameConfig>,
+    upgrade_manager: Res<UpgradeManager>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut aim: ResMut<PlayerAim>,
+    player_query: Query<(&Transform, &PlayerMovement, &Player)>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    projectile_query: Query<&Transform, With<EnemyProjectile>>,
+    loot_query: Query<&Transform, With<Loot>>,
+) {
+    // There's no input plugin here, so clear last frame's presses the way it would
+    keys.clear();
+    mouse.clear();
+
+    let Ok((player_transform, movement, player)) = player_query.get_single() else {
+        return;
+    };
+    if !player.is_alive {
+        return;
+    }
+
+    let flat = |transform: &Transform| Vec2::new(transform.translation.x, transform.translation.z);
+    let position = flat(player_transform);
+    let nearest = |targets: &mut dyn Iterator<Item = Vec2>| {
+        targets.min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)))
+    };
+    let nearest_enemy = nearest(&mut enemy_query.iter().map(flat));
+
+    // Kite: back away from nearby enemies and their shots
+    let mut steer = Vec2::ZERO;
+    for enemy in enemy_query.iter().map(flat) {
+        let away = position - enemy;
+        let distance = away.length();
+        if distance < DANGER_RADIUS {
+            steer += away.normalize_or_zero() * (1.0 - distance / DANGER_RADIUS);
+        }
+    }
+    for shot in projectile_query.iter().map(flat) {
+        let away = position - shot;
+        let distance = away.length();
+        if distance < DODGE_RADIUS {
+            steer += away.normalize_or_zero() * (1.0 - distance / DODGE_RADIUS) * 2.0;
+        }
+    }
+
+    // Nothing close: grab loot, or go looking for the fight
+    if steer.length() < 0.05 {
+        let loot = nearest(&mut loot_query.iter().map(flat)).filter(|loot| loot.distance(position) < LOOT_RADIUS);
+        if let Some(loot) = loot {
+            steer = (loot - position).normalize_or_zero();
+        } else if let Some(enemy) = nearest_enemy.filter(|enemy| enemy.distance(position) > SEEK_DISTANCE) {
+            steer = (enemy - position).normalize_or_zero() * 0.5;
+        }
+    }
+
+    // Stay off the walls, where kiting runs out of room
+    let edge = config.arena_bounds * 0.75;
+    if position.length() > edge {
+        steer -= position.normalize_or_zero() * ((position.length() - edge) / (config.arena_bounds - edge)) * 2.0;
+    }
+
+    // Tank controls: turn towards the goal, thrust once roughly facing it
+    let mut turn_left = false;
+    let mut turn_right = false;
+    let mut thrust = false;
+    if steer.length() > 0.05 {
+        let goal = steer.x.atan2(steer.y);
+        let error = (goal - movement.current_direction + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        turn_right = error > TURN_DEADZONE;
+        turn_left = error < -TURN_DEADZONE;
+        thrust = error.abs() < THRUST_CONE;
+    }
+    hold(&mut keys, KeyCode::A, turn_left);
+    hold(&mut keys, KeyCode::D, turn_right);
+    hold(&mut keys, KeyCode::W, thrust);
+
+    // Shoot at whatever is closest; aim is a screen offset with up along -Z
+    aim.cursor_offset = nearest_enemy.map(|enemy| {
+        let offset = enemy - position;
+        Vec2::new(offset.x, -offset.y)
+    });
+    hold(&mut mouse, MouseButton::Left, aim.cursor_offset.is_some());
+
+    // Always take the first upgrade on offer. Key1 may still be held from a break pick,
+    // so tap it afresh on the level-up screen
+    let level_up = !upgrade_manager.level_up_offers.is_empty();
+    let tap = level_up && !keys.pressed(KeyCode::Key1);
+    hold(&mut keys, KeyCode::Key1, tap || (!level_up && upgrade_manager.upgrade_selection_active));
+}
+
+fn hold<T: Copy + Eq + std::hash::Hash + Send + Sync + 'static>(input: &mut Input<T>, button: T, held: bool) {
+    if held && !input.pressed(button) {
+        input.press(button);
+    } else if !held && input.pressed(button) {
+        input.release(button);
+    }
+}
+
+fn track_waves_system(
+    time: Res<Time>,
+    wave_manager: Res<WaveManager>,
+    game_data: Res<GameData>,
+    mut sim_run: ResMut<SimRun>,
+    mut wave_events: EventReader<WaveCompleteEvent>,
+    player_query: Query<&Player>,
+) {
+    if sim_run.finished {
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let level = player_query.get_single().map_or(1, |player| player.level);
+
+    for event in wave_events.read() {
+        let time_to_clear = sim_run.wave_started_at.take().map_or(event.clear_time, |start| now - start);
+        sim_run.finished = event.wave_number >= sim_run.target_wave;
+        sim_run.record(event.wave_number, true, time_to_clear, event.damage_taken, level, game_data.score);
+    }
+
+    if wave_manager.wave_active && sim_run.wave_started_at.is_none() {
+        sim_run.wave_started_at = Some(now);
+    }
+
+    if player_query.get_single().is_ok_and(|player| !player.is_alive) && !sim_run.finished {
+        sim_run.finished = true;
+        let time = sim_run.wave_started_at.map_or(0.0, |start| now - start);
+        sim_run.record(wave_manager.current_wave, false, time, wave_manager.wave_damage_taken, level, game_data.score);
+    }
+}
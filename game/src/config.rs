@@ -20,6 +20,10 @@ pub struct GameConfig {
     pub difficulty_scaling: f32,
     pub loot_chance_multiplier: f32,
     pub experience_multiplier: f32,
+    pub tick_rate: f32, // Gameplay and physics ticks per second
+    pub loot_lifetime: f32, // Seconds a pickup lies around before it fades out
+    pub max_experience_orbs: usize, // Past this many, nearby orbs merge into bigger gems
+    pub orb_merge_radius: f32,
 }
 
 impl Default for GameConfig {
@@ -29,6 +33,10 @@ impl Default for GameConfig {
             difficulty_scaling: 1.2,
             loot_chance_multiplier: 1.0,
             experience_multiplier: 1.0,
+            tick_rate: 60.0,
+            loot_lifetime: 30.0,
+            max_experience_orbs: 150,
+            orb_merge_radius: 60.0,
         }
     }
 }
@@ -134,6 +142,14 @@ pub struct WaveConfig {
     pub spawn_interval_reduction: f32,
     pub break_duration: f32,
     pub difficulty_multiplier_per_wave: f32,
+    pub spawn_telegraph_time: f32,
+    pub min_spawn_distance: f32,
+    pub director_enabled: bool,
+    pub director_min_intensity: f32,
+    pub director_max_intensity: f32,
+    pub director_adjust_rate: f32,
+    pub break_upgrade_pick: bool,
+    pub break_skip_bonus_per_second: u32,
 }
 
 impl Default for WaveConfig {
@@ -145,6 +161,14 @@ impl Default for WaveConfig {
             spawn_interval_reduction: 0.1,
             break_duration: 10.0,
             difficulty_multiplier_per_wave: 0.2,
+            spawn_telegraph_time: 1.0,
+            min_spawn_distance: 120.0,
+            director_enabled: true,
+            director_min_intensity: 0.6,
+            director_max_intensity: 1.5,
+            director_adjust_rate: 0.05,
+            break_upgrade_pick: true,
+            break_skip_bonus_per_second: 10,
         }
     }
 }
@@ -196,6 +220,11 @@ impl EnemyConfig {
 }
 
 impl WaveConfig {
+    /// How much tougher enemies in `wave` are than in the first one
+    pub fn difficulty_multiplier(&self, wave: u32) -> f32 {
+        1.0 + wave.saturating_sub(1) as f32 * self.difficulty_multiplier_per_wave
+    }
+    
     pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
         let config_str = std::fs::read_to_string("config/wave_config.ron")?;
         let config: WaveConfig = ron::de::from_str(&config_str)?;
@@ -1,19 +1,29 @@
 //! Enemy system - handles different enemy types, AI behavior, and spawning
 use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use crate::{GameState, GameEntity};
 use crate::player::{Player, Projectile};
+use crate::affix::{apply_affixes, roll_affixes, AffixType, Corrosive, Freezing, Reflective, Shielded, Vampiric};
+use crate::rng::GameRng;
+use crate::simulation::{GameplaySet, InterpolatedTransform};
+use crate::status::{StatusEffect, StatusEffectType, StatusEffects};
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Update, (
+            .add_event::<EnemyKilledEvent>()
+            .add_systems(FixedUpdate, (
                 enemy_ai_system,
                 enemy_combat_system,
-                enemy_health_system,
                 projectile_collision_system,
-            ).run_if(in_state(GameState::Playing)));
+                enemy_health_system,
+                update_enemy_projectiles_system,
+            ).chain()
+             .in_set(GameplaySet::Enemies)
+             .run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -27,6 +37,19 @@ pub struct Enemy {
     pub last_damage_time: f32,
     pub experience_value: f32,
     pub loot_chance: f32,
+    pub last_attacker: Option<Entity>,
+}
+
+// Fired once when an enemy dies. The entity is despawned straight after, so
+// everything responders need is copied into the event.
+#[derive(Event)]
+pub struct EnemyKilledEvent {
+    pub entity: Entity,
+    pub enemy_type: EnemyType,
+    pub position: Vec3,
+    pub killer: Option<Entity>,
+    pub experience_value: f32,
+    pub loot_chance: f32,
 }
 
 #[derive(Component)]
@@ -36,7 +59,7 @@ pub struct EnemyMovement {
     pub behavior: MovementBehavior,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EnemyType {
     Chaser,
     Shooter,
@@ -46,6 +69,45 @@ pub enum EnemyType {
     Boss,
 }
 
+// Stat scaling and extra affixes applied on top of an enemy type's base stats
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemyModifiers {
+    pub health_multiplier: f32,
+    pub damage_multiplier: f32,
+    pub speed_multiplier: f32,
+    pub affixes: Vec<AffixType>,
+}
+
+impl Default for EnemyModifiers {
+    fn default() -> Self {
+        Self {
+            health_multiplier: 1.0,
+            damage_multiplier: 1.0,
+            speed_multiplier: 1.0,
+            affixes: Vec::new(),
+        }
+    }
+}
+
+impl EnemyModifiers {
+    pub fn combine(&self, other: &EnemyModifiers) -> Self {
+        let mut affixes = self.affixes.clone();
+        for affix in &other.affixes {
+            if !affixes.contains(affix) {
+                affixes.push(*affix);
+            }
+        }
+        
+        Self {
+            health_multiplier: self.health_multiplier * other.health_multiplier,
+            damage_multiplier: self.damage_multiplier * other.damage_multiplier,
+            speed_multiplier: self.speed_multiplier * other.speed_multiplier,
+            affixes,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum MovementBehavior {
     ChasePlayer,
@@ -72,6 +134,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 10.0,
                 loot_chance: 0.1,
+                last_attacker: None,
             },
             EnemyType::Shooter => Self {
                 health: 30.0,
@@ -82,6 +145,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 15.0,
                 loot_chance: 0.15,
+                last_attacker: None,
             },
             EnemyType::Tank => Self {
                 health: 150.0,
@@ -92,6 +156,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 25.0,
                 loot_chance: 0.3,
+                last_attacker: None,
             },
             EnemyType::Swarm => Self {
                 health: 20.0,
@@ -102,6 +167,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 5.0,
                 loot_chance: 0.05,
+                last_attacker: None,
             },
             EnemyType::Elite => Self {
                 health: 100.0,
@@ -112,6 +178,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 50.0,
                 loot_chance: 0.5,
+                last_attacker: None,
             },
             EnemyType::Boss => Self {
                 health: 500.0,
@@ -122,6 +189,7 @@ impl Enemy {
                 last_damage_time: 0.0,
                 experience_value: 200.0,
                 loot_chance: 0.9,
+                last_attacker: None,
             },
         }
     }
@@ -141,10 +209,27 @@ impl Enemy {
 pub fn spawn_enemy(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
+    rng: &mut impl Rng,
     enemy_type: EnemyType,
     position: Vec3,
-) {
-    let enemy = Enemy::new(enemy_type.clone());
+) -> Entity {
+    spawn_enemy_with_modifiers(commands, asset_server, rng, enemy_type, position, &EnemyModifiers::default())
+}
+
+pub fn spawn_enemy_with_modifiers(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    rng: &mut impl Rng,
+    enemy_type: EnemyType,
+    position: Vec3,
+    modifiers: &EnemyModifiers,
+) -> Entity {
+    let mut enemy = Enemy::new(enemy_type.clone());
+    enemy.max_health *= modifiers.health_multiplier;
+    enemy.health = enemy.max_health;
+    enemy.damage *= modifiers.damage_multiplier;
+    enemy.speed *= modifiers.speed_multiplier;
+    
     let (model_path, scale) = match enemy_type {
         EnemyType::Chaser => ("models/orb.gltf", 0.4),
         EnemyType::Swarm => ("models/orb.gltf", 0.3),
@@ -154,33 +239,58 @@ pub fn spawn_enemy(
         EnemyType::Boss => ("models/orb_shield.gltf", 1.2),
     };
     
-    commands.spawn((
+    // Elites always roll a set of affixes, on top of any the modifiers force
+    let mut affixes = modifiers.affixes.clone();
+    if enemy_type == EnemyType::Elite {
+        for affix in roll_affixes(rng, 1, 3) {
+            if !affixes.contains(&affix) {
+                affixes.push(affix);
+            }
+        }
+    }
+    
+    let entity = commands.spawn((
         SceneBundle {
             scene: asset_server.load(format!("{}#Scene0", model_path)),
             transform: Transform::from_translation(position)
                 .with_scale(Vec3::splat(scale)),
             ..default()
         },
-        enemy,
         EnemyMovement {
             velocity: Vec3::ZERO,
             target_position: position,
             behavior: MovementBehavior::ChasePlayer,
         },
+        StatusEffects::default(),
+        InterpolatedTransform::default(),
         GameEntity,
         Name::new(format!("Enemy_{:?}", enemy_type)),
-    ));
+    )).id();
+    
+    apply_affixes(commands, entity, &enemy, &affixes);
+    commands.entity(entity).insert(enemy);
+    
+    entity
 }
 
 // AI System
 fn enemy_ai_system(
     time: Res<Time>,
-    mut enemy_query: Query<(&mut Transform, &mut EnemyMovement, &Enemy)>,
-    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<(&mut Transform, &mut EnemyMovement, &Enemy, Option<&StatusEffects>)>,
+    player_query: Query<(&Transform, &Player), Without<Enemy>>,
 ) {
-    if let Ok(player_transform) = player_query.get_single() {
-        for (mut transform, mut movement, enemy) in enemy_query.iter_mut() {
+    if let Ok((player_transform, player)) = player_query.get_single() {
+        for (mut transform, mut movement, enemy, status) in enemy_query.iter_mut() {
             let dt = time.delta_seconds();
+            let speed = enemy.speed
+                * status.map_or(1.0, |s| s.speed_multiplier())
+                * player.buffs.enemy_speed_multiplier; // Time Slow
+            
+            // Frozen enemies don't move at all
+            if speed <= 0.0 {
+                movement.velocity = Vec3::ZERO;
+                continue;
+            }
             
             match movement.behavior {
                 MovementBehavior::ChasePlayer => {
@@ -188,7 +298,7 @@ fn enemy_ai_system(
                     let mut direction = player_transform.translation - transform.translation;
                     direction.y = 0.0; // Keep movement in horizontal plane
                     direction = direction.normalize();
-                    movement.velocity = direction * enemy.speed;
+                    movement.velocity = direction * speed;
                     
                     // Apply movement
                     transform.translation += movement.velocity * dt;
@@ -206,7 +316,7 @@ fn enemy_ai_system(
                     }
                     
                     let direction = to_target.normalize();
-                    movement.velocity = direction * enemy.speed;
+                    movement.velocity = direction * speed;
                     transform.translation += movement.velocity * dt;
                 }
                 MovementBehavior::Circle(center, radius) => {
@@ -216,7 +326,7 @@ fn enemy_ai_system(
                         transform.translation.z - center.z,
                     );
                     let current_angle = current_pos_2d.angle_between(Vec2::X);
-                    let new_angle = current_angle + enemy.speed * dt / radius;
+                    let new_angle = current_angle + speed * dt / radius;
                     
                     let new_pos = center + Vec3::new(
                         new_angle.cos() * radius,
@@ -240,28 +350,34 @@ fn enemy_combat_system(
     mut commands: Commands,
     time: Res<Time>,
     asset_server: Res<AssetServer>,
-    mut enemy_query: Query<(&Transform, &mut Enemy)>,
+    mut enemy_query: Query<(Entity, &Transform, &mut Enemy, Option<&StatusEffects>)>,
     player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
 ) {
     if let Ok(player_transform) = player_query.get_single() {
-        for (enemy_transform, mut enemy) in enemy_query.iter_mut() {
+        for (enemy_entity, enemy_transform, mut enemy, status) in enemy_query.iter_mut() {
+            // Frozen enemies can't attack
+            if status.is_some_and(|s| s.is_frozen()) {
+                continue;
+            }
+            
+            let damage = enemy.damage * status.map_or(1.0, |s| s.damage_dealt_multiplier());
             let distance = enemy_transform.translation.distance(player_transform.translation);
             
             // Different combat behaviors based on enemy type
             match enemy.enemy_type {
-                EnemyType::Shooter | EnemyType::Elite | EnemyType::Boss => {
-                    if distance < 300.0 && time.elapsed_seconds() - enemy.last_damage_time > 2.0 {
-                        // Spawn enemy projectile
-                        let direction = (player_transform.translation - enemy_transform.translation).normalize();
-                        spawn_enemy_projectile(
-                            &mut commands,
-                            &asset_server,
-                            enemy_transform.translation,
-                            direction,
-                            enemy.damage,
-                        );
-                        enemy.last_damage_time = time.elapsed_seconds();
-                    }
+                EnemyType::Shooter | EnemyType::Elite | EnemyType::Boss
+                    if distance < 300.0 && time.elapsed_seconds() - enemy.last_damage_time > 2.0 => {
+                    // Spawn enemy projectile
+                    let direction = (player_transform.translation - enemy_transform.translation).normalize();
+                    spawn_enemy_projectile(
+                        &mut commands,
+                        &asset_server,
+                        enemy_transform.translation,
+                        direction,
+                        damage,
+                        Some(enemy_entity),
+                    );
+                    enemy.last_damage_time = time.elapsed_seconds();
                 }
                 _ => {
                     // Melee enemies just chase - damage is handled by collision
@@ -272,46 +388,105 @@ fn enemy_combat_system(
 }
 
 // Health System
-fn enemy_health_system(
+pub fn enemy_health_system(
     mut commands: Commands,
-    mut enemy_query: Query<(Entity, &Enemy)>,
-    mut player_query: Query<&mut Player>,
+    enemy_query: Query<(Entity, &Transform, &Enemy)>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
 ) {
-    for (entity, enemy) in enemy_query.iter() {
+    for (entity, transform, enemy) in enemy_query.iter() {
         if enemy.is_dead() {
-            // Give experience to player
-            if let Ok(mut player) = player_query.get_single_mut() {
-                let leveled_up = player.add_experience(enemy.experience_value);
-                if leveled_up {
-                    info!("Player leveled up to level {}!", player.level);
-                }
-            }
-            
-            // TODO: Spawn loot based on loot_chance
-            // TODO: Spawn death effects
+            // Loot, XP orbs, death FX and stats all react to this event
+            killed_events.send(EnemyKilledEvent {
+                entity,
+                enemy_type: enemy.enemy_type.clone(),
+                position: transform.translation,
+                killer: enemy.last_attacker,
+                experience_value: enemy.experience_value,
+                loot_chance: enemy.loot_chance,
+            });
             
-            commands.entity(entity).despawn();
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
 // Projectile collision system
-fn projectile_collision_system(
+const SPLASH_DAMAGE_FRACTION: f32 = 0.5; // Explosions hit bystanders for this share of the shot's damage
+
+type EnemyHitTarget<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut Enemy,
+    Option<&'a mut Shielded>,
+    Option<&'a Reflective>,
+    Option<&'a mut StatusEffects>,
+);
+
+pub fn projectile_collision_system(
     mut commands: Commands,
     time: Res<Time>,
-    projectile_query: Query<(Entity, &Transform, &Projectile)>,
-    mut enemy_query: Query<(Entity, &Transform, &mut Enemy)>,
-    mut player_query: Query<&mut Player>,
+    asset_server: Res<AssetServer>,
+    mut game_rng: ResMut<GameRng>,
+    mut projectile_query: Query<(Entity, &Transform, &mut Projectile)>,
+    mut enemy_query: Query<EnemyHitTarget>,
 ) {
     let current_time = time.elapsed_seconds();
     
-    for (projectile_entity, projectile_transform, projectile) in projectile_query.iter() {
-        for (enemy_entity, enemy_transform, mut enemy) in enemy_query.iter_mut() {
+    // Explosions from this tick's hits, applied once every shot has landed
+    let mut splashes = Vec::new();
+    
+    for (projectile_entity, projectile_transform, mut projectile) in projectile_query.iter_mut() {
+        for (enemy_entity, enemy_transform, mut enemy, shielded, reflective, status) in enemy_query.iter_mut() {
+            if projectile.hits.contains(&enemy_entity) {
+                continue;
+            }
+            
             let distance = projectile_transform.translation.distance(enemy_transform.translation);
             
             if distance < 20.0 { // Simple collision detection
-                let is_dead = enemy.take_damage(projectile.damage, current_time);
-                commands.entity(projectile_entity).despawn();
+                // Reflective enemies can bounce the shot back at the player
+                if let Some(reflective) = reflective {
+                    if game_rng.combat().gen_bool(reflective.chance as f64) {
+                        spawn_enemy_projectile(
+                            &mut commands,
+                            &asset_server,
+                            enemy_transform.translation,
+                            -projectile.velocity.normalize_or_zero(),
+                            projectile.damage,
+                            Some(enemy_entity),
+                        );
+                        commands.entity(projectile_entity).despawn();
+                        break;
+                    }
+                }
+                
+                // Piercing shots carry on until they've passed through enough enemies
+                if projectile.pierce > 0 {
+                    projectile.pierce -= 1;
+                    projectile.hits.push(enemy_entity);
+                } else {
+                    commands.entity(projectile_entity).despawn();
+                }
+                
+                if projectile.splash_radius > 0.0 {
+                    splashes.push((enemy_transform.translation, enemy_entity, projectile.splash_radius, projectile.damage, projectile.owner));
+                }
+                
+                // Vulnerability amplifies the hit, then the projectile's effects land
+                let mut damage = projectile.damage;
+                if let Some(mut status) = status {
+                    damage *= status.damage_taken_multiplier();
+                    for effect in &projectile.on_hit_effects {
+                        status.apply(effect.clone());
+                    }
+                }
+                
+                // Shields soak damage before health
+                if let Some(mut shielded) = shielded {
+                    damage = shielded.absorb(damage, current_time);
+                }
+                enemy.last_attacker = projectile.owner;
+                let is_dead = enemy.take_damage(damage, current_time);
                 
                 if is_dead {
                     info!("Enemy destroyed!");
@@ -320,6 +495,22 @@ fn projectile_collision_system(
             }
         }
     }
+    
+    // Splash damage hits everything else around the impact
+    for (center, direct_target, radius, damage, owner) in splashes {
+        for (enemy_entity, enemy_transform, mut enemy, shielded, _, status) in enemy_query.iter_mut() {
+            if enemy_entity == direct_target || enemy_transform.translation.distance(center) > radius {
+                continue;
+            }
+            
+            let mut damage = damage * SPLASH_DAMAGE_FRACTION * status.map_or(1.0, |s| s.damage_taken_multiplier());
+            if let Some(mut shielded) = shielded {
+                damage = shielded.absorb(damage, current_time);
+            }
+            enemy.last_attacker = owner;
+            enemy.take_damage(damage, current_time);
+        }
+    }
 }
 
 // Enemy projectile system
@@ -329,6 +520,7 @@ pub struct EnemyProjectile {
     pub damage: f32,
     pub lifetime: f32,
     pub max_lifetime: f32,
+    pub source: Option<Entity>,
 }
 
 fn spawn_enemy_projectile(
@@ -337,6 +529,7 @@ fn spawn_enemy_projectile(
     position: Vec3,
     direction: Vec3,
     damage: f32,
+    source: Option<Entity>,
 ) {
     commands.spawn((
         SpriteBundle {
@@ -350,7 +543,9 @@ fn spawn_enemy_projectile(
             damage,
             lifetime: 0.0,
             max_lifetime: 3.0,
+            source,
         },
+        InterpolatedTransform::default(),
         GameEntity,
         Name::new("EnemyProjectile"),
     ));
@@ -361,20 +556,45 @@ pub fn update_enemy_projectiles_system(
     mut commands: Commands,
     time: Res<Time>,
     mut projectile_query: Query<(Entity, &mut Transform, &mut EnemyProjectile)>,
-    mut player_query: Query<(&Transform, &mut Player), Without<EnemyProjectile>>,
+    mut player_query: Query<(&Transform, &mut Player, Option<&mut StatusEffects>), Without<EnemyProjectile>>,
+    mut vampiric_query: Query<(&mut Enemy, &Vampiric)>,
+    status_affix_query: Query<(Option<&Freezing>, Option<&Corrosive>)>,
 ) {
-    if let Ok((player_transform, mut player)) = player_query.get_single_mut() {
+    if let Ok((player_transform, mut player, mut status)) = player_query.get_single_mut() {
+        // Time Slow slows enemy shots down too, without shortening their range
+        let dt = time.delta_seconds() * player.buffs.enemy_speed_multiplier;
         for (entity, mut transform, mut projectile) in projectile_query.iter_mut() {
             // Move projectile
-            transform.translation += projectile.velocity * time.delta_seconds();
+            transform.translation += projectile.velocity * dt;
             
             // Update lifetime
-            projectile.lifetime += time.delta_seconds();
+            projectile.lifetime += dt;
             
             // Check collision with player
             let distance = transform.translation.distance(player_transform.translation);
             if distance < 25.0 {
-                player.take_damage(projectile.damage);
+                player.take_damage(projectile.damage * status.as_ref().map_or(1.0, |s| s.damage_taken_multiplier()));
+                
+                // Freezing and corrosive shooters leave their mark on the player
+                if let (Some(source), Some(status)) = (projectile.source, status.as_mut()) {
+                    if let Ok((freezing, corrosive)) = status_affix_query.get(source) {
+                        if freezing.is_some() {
+                            status.apply(StatusEffect::new(StatusEffectType::Freeze));
+                        }
+                        if corrosive.is_some() {
+                            status.apply(StatusEffect::new(StatusEffectType::Vulnerability));
+                        }
+                    }
+                }
+                
+                // Vampiric shooters heal off the damage they deal
+                if let Some(source) = projectile.source {
+                    if let Ok((mut enemy, vampiric)) = vampiric_query.get_mut(source) {
+                        enemy.health = (enemy.health + projectile.damage * vampiric.heal_fraction)
+                            .min(enemy.max_health);
+                    }
+                }
+                
                 commands.entity(entity).despawn();
                 continue;
             }
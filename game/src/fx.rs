@@ -1,6 +1,9 @@
 //! Visual effects system - handles particles, explosions, and other visual feedback
 use bevy::prelude::*;
+use rand::Rng;
 use crate::GameState;
+use crate::enemy::{EnemyKilledEvent, EnemyType};
+use crate::rng::GameRng;
 
 pub struct FXPlugin;
 
@@ -11,6 +14,7 @@ impl Plugin for FXPlugin {
             .add_systems(Update, (
                 update_particles_system,
                 cleanup_particles_system,
+                death_explosion_system,
             ).run_if(in_state(GameState::Playing)));
     }
 }
@@ -41,11 +45,10 @@ impl ParticleManager {
     pub fn spawn_explosion(
         &mut self,
         commands: &mut Commands,
+        rng: &mut impl Rng,
         position: Vec3,
         particle_count: u32,
     ) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
         
         for _ in 0..particle_count {
             let velocity = Vec3::new(
@@ -80,7 +83,7 @@ impl ParticleManager {
     
     pub fn spawn_damage_numbers(
         &mut self,
-        commands: &mut Commands,
+        _commands: &mut Commands,
         position: Vec3,
         damage: f32,
     ) {
@@ -134,12 +137,11 @@ pub enum PickupEffectType {
 
 pub fn update_particles_system(
     time: Res<Time>,
-    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
-    mut particle_manager: ResMut<ParticleManager>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite)>,
 ) {
     let dt = time.delta_seconds();
     
-    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+    for (mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
         // Update particle lifetime
         particle.lifetime += dt;
         
@@ -165,6 +167,23 @@ pub fn update_particles_system(
     }
 }
 
+pub fn death_explosion_system(
+    mut commands: Commands,
+    mut particle_manager: ResMut<ParticleManager>,
+    mut game_rng: ResMut<GameRng>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    for event in killed_events.read() {
+        let particle_count = match event.enemy_type {
+            EnemyType::Swarm => 8,
+            EnemyType::Chaser | EnemyType::Shooter => 15,
+            EnemyType::Tank | EnemyType::Elite => 25,
+            EnemyType::Boss => 60,
+        };
+        particle_manager.spawn_explosion(&mut commands, game_rng.cosmetics(), event.position, particle_count);
+    }
+}
+
 pub fn cleanup_particles_system(
     mut commands: Commands,
     particle_query: Query<(Entity, &Particle)>,
@@ -6,14 +6,21 @@
 //! - Wave-based gameplay with increasing difficulty
 //! - Loot collection and player upgrades
 //! - Particle effects and visual feedback
+//!
+//! `VoidSurvivorPlugin` assembles the whole game, so the game binary, the simulator and tests
+//! all build their `App` from the same pieces.
 
 use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::transform::TransformSystem;
+use bevy_rapier3d::prelude::*;
 
 // Re-export Bevy for convenience
 pub use bevy;
 
 // Game modules
 pub mod player;
+pub mod powerup;
 pub mod enemy;
 pub mod wave;
 pub mod loot;
@@ -23,6 +30,132 @@ pub mod fx;
 pub mod audio;
 pub mod config;
 pub mod asteroid;
+pub mod affix;
+pub mod replay;
+pub mod rng;
+pub mod shop;
+pub mod simulation;
+pub mod status;
+pub mod survival;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+use affix::AffixPlugin;
+use asteroid::AsteroidPlugin;
+use audio::AudioPlugin;
+use config::ConfigPlugin;
+use enemy::EnemyPlugin;
+use fx::FXPlugin;
+use loot::LootPlugin;
+use player::PlayerPlugin;
+use powerup::PowerUpPlugin;
+use replay::{ReplayPlugin, ReplayRecorder, ReplayViewerPlugin};
+use rng::{GameRng, RngPlugin};
+use shop::ShopPlugin;
+use simulation::{SimulationPlugin, SimulationSet};
+use status::StatusPlugin;
+use survival::SurvivalPlugin;
+use ui::UIPlugin;
+use upgrade::UpgradePlugin;
+use wave::WavePlugin;
+
+/// The whole game. The windowed build adds it after `DefaultPlugins`.
+///
+/// With `headless` set it brings its own minimal Bevy setup instead and leaves out the camera,
+/// menus, HUD, audio, the replay viewer and physics debug drawing, for tools and tests that drive
+/// the simulation directly. Headless runs start from seed 0, and every new run starts over from
+/// the same seed; insert a `GameRng` to pick another. Replays are only recorded there once
+/// `ReplayRecorder::enabled` is set.
+#[derive(Default)]
+pub struct VoidSurvivorPlugin {
+    pub headless: bool,
+}
+
+impl VoidSurvivorPlugin {
+    pub fn headless() -> Self {
+        Self { headless: true }
+    }
+}
+
+impl Plugin for VoidSurvivorPlugin {
+    fn build(&self, app: &mut App) {
+        if self.headless {
+            app
+                .add_plugins((
+                    MinimalPlugins,
+                    AssetPlugin::default(),
+                    ScenePlugin,
+                    TransformPlugin,
+                    HierarchyPlugin,
+                ))
+                // Asset types gameplay spawns reference, without anything to render them
+                .init_asset::<Mesh>()
+                .init_asset::<StandardMaterial>()
+                .init_asset::<Image>()
+                .init_asset::<Font>()
+                .init_resource::<Input<KeyCode>>()
+                .init_resource::<Input<MouseButton>>()
+                .init_resource::<Input<GamepadButton>>()
+                .insert_resource(GameRng::new(0))
+                .insert_resource(ReplayRecorder { enabled: false, ..default() });
+        }
+
+        app
+            .add_state::<GameState>()
+            .insert_resource(GameData::default())
+            .init_resource::<GameMode>()
+            .insert_resource(RapierConfiguration {
+                gravity: Vec3::ZERO, // Disable gravity for top-down space game
+                timestep_mode: TimestepMode::Fixed { dt: 1.0 / 60.0, substeps: 1 }, // Rate set from GameConfig at startup
+                ..default()
+            });
+
+        if !self.headless {
+            app.add_plugins((RngPlugin, ReplayViewerPlugin)); // Before gameplay plugins so every run starts seeded
+        }
+
+        app
+            // Clear out the last run before the new one spawns anything
+            .add_systems(OnExit(GameState::MainMenu), start_new_run_system)
+            .add_plugins((
+                ReplayPlugin,
+                SimulationPlugin,
+                RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(), // Steps with gameplay ticks
+            ))
+            .add_plugins((
+                ConfigPlugin,
+                PlayerPlugin,
+                EnemyPlugin,
+                WavePlugin,
+                LootPlugin,
+                PowerUpPlugin,
+                UpgradePlugin,
+                ShopPlugin,
+                FXPlugin,
+                AsteroidPlugin,
+                AffixPlugin,
+                StatusPlugin,
+                SurvivalPlugin,
+            ));
+
+        if self.headless {
+            return;
+        }
+
+        app
+            .add_plugins((
+                RapierDebugRenderPlugin::default(), // Optional: for debugging physics shapes
+                UIPlugin,
+                AudioPlugin,
+            ))
+            .add_systems(Startup, setup_game)
+            .add_systems(Update, handle_game_state_transitions)
+            // Follow the interpolated ship, not the last tick's position
+            .add_systems(PostUpdate, update_camera_system
+                .after(SimulationSet::Interpolate)
+                .before(TransformSystem::TransformPropagate));
+    }
+}
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 pub enum GameState {
@@ -30,11 +163,172 @@ pub enum GameState {
     MainMenu,
     Playing,
     Paused,
+    LevelUp, // Gameplay held while the player picks an upgrade
     GameOver,
 }
 
+// Selected from the main menu
+#[derive(Resource, Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub enum GameMode {
+    #[default]
+    Waves,
+    Survival, // Survive a fixed time against continuous spawns
+}
+
 #[derive(Component)]
 pub struct GameEntity;
 
 #[derive(Component)]
 pub struct MainCamera;
+
+// Everything that belongs to a run and goes away when a new one starts
+pub type RunEntityFilter = Or<(With<GameEntity>, With<ui::GameUI>)>;
+
+#[derive(Resource)]
+pub struct GameData {
+    pub score: u32,
+    pub current_wave: u32,
+    pub game_time: f32,
+}
+
+impl Default for GameData {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            current_wave: 1,
+            game_time: 0.0,
+        }
+    }
+}
+
+// Systems
+fn setup_game(mut commands: Commands) {
+    // Setup 3D camera for true top-down view
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 20.0, 0.0)
+                .looking_at(Vec3::ZERO, Vec3::NEG_Z), // Use negative Z as up vector for proper top-down orientation
+            camera: Camera {
+                order: 0,
+                ..default()
+            },
+            ..default()
+        },
+        MainCamera,
+    ));
+    
+    // Add basic lighting for 3D models
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            color: Color::WHITE,
+            illuminance: 10000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.5, -0.5, 0.0)),
+        ..default()
+    });
+    
+    // Add ambient lighting
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 0.3,
+    });
+    
+    info!("Game setup complete - Void Survivor initialized with 3D rendering");
+}
+
+/// Despawns the last run's entities and HUD and puts every run resource back to its default, so
+/// the next run plays out from its seed alone
+pub fn reset_run(
+    commands: &mut Commands,
+    run_entities: &Query<Entity, RunEntityFilter>,
+) {
+    for entity in run_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.insert_resource(GameData::default());
+    commands.insert_resource(wave::WaveManager::default());
+    commands.insert_resource(wave::WaveStats::default());
+    commands.insert_resource(wave::AIDirector::default());
+    commands.insert_resource(loot::LootPity::default());
+    commands.insert_resource(survival::SurvivalRun::default());
+    commands.insert_resource(upgrade::UpgradeManager::default());
+    commands.insert_resource(shop::Shop::default());
+    commands.insert_resource(fx::ParticleManager::default());
+}
+
+fn start_new_run_system(
+    mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+    run_entities: Query<Entity, RunEntityFilter>,
+) {
+    reset_run(&mut commands, &run_entities);
+
+    // Rewind every stream, in case nothing picks a new seed for this run
+    let seed = game_rng.seed();
+    *game_rng = GameRng::new(seed);
+}
+
+fn handle_game_state_transitions(
+    keyboard_input: Res<Input<KeyCode>>,
+    current_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    match current_state.get() {
+        GameState::MainMenu => {
+            if keyboard_input.just_pressed(KeyCode::Space) {
+                next_state.set(GameState::Playing);
+                info!("Starting game");
+            }
+        }
+        GameState::Playing => {
+            if keyboard_input.just_pressed(KeyCode::Escape) {
+                next_state.set(GameState::Paused);
+                info!("Game paused");
+            }
+        }
+        GameState::Paused => {
+            if keyboard_input.just_pressed(KeyCode::Escape) {
+                next_state.set(GameState::Playing);
+                info!("Game resumed");
+            }
+        }
+        GameState::LevelUp => {
+            // Only picking an upgrade leaves the level-up screen
+        }
+        GameState::GameOver => {
+            if keyboard_input.just_pressed(KeyCode::Space) {
+                next_state.set(GameState::MainMenu);
+                info!("Returning to main menu");
+            }
+        }
+    }
+}
+
+fn update_camera_system(
+    mut camera_query: Query<&mut Transform, (With<MainCamera>, Without<player::Player>)>,
+    player_query: Query<&Transform, (With<player::Player>, Without<MainCamera>)>,
+) {
+    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+        if let Ok(player_transform) = player_query.get_single() {
+            // Keep camera directly above the player for true top-down view
+            let target = Vec3::new(
+                player_transform.translation.x,
+                20.0, // Keep camera height constant
+                player_transform.translation.z,
+            );
+            // Use faster lerp for more responsive camera (0.15 instead of 0.05)
+            camera_transform.translation = camera_transform.translation.lerp(target, 0.15);
+            
+            // Always look straight down at the player with correct orientation
+            let look_target = Vec3::new(
+                player_transform.translation.x,
+                0.0, // Look at ground level
+                player_transform.translation.z,
+            );
+            camera_transform.look_at(look_target, Vec3::NEG_Z); // Use NEG_Z as up vector for consistent orientation
+        }
+    }
+}
@@ -1,16 +1,45 @@
 //! Loot system - handles item drops, pickups, and rewards
 use bevy::prelude::*;
-use crate::{GameState, GameEntity};
-use crate::player::Player;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{GameMode, GameState, GameEntity};
+use crate::config::GameConfig;
+use crate::enemy::{EnemyKilledEvent, EnemyType};
+use crate::player::{Player, WeaponGainedEvent};
+use crate::powerup::{PowerUpCollectedEvent, PowerUpType};
+use crate::rng::GameRng;
+use crate::simulation::{GameplaySet, InterpolatedTransform};
+use crate::survival::SurvivalRun;
+use crate::upgrade::{Upgrade, UpgradeCatalogue, UpgradeManager, UpgradeType};
+use crate::wave::WaveManager;
+
+const LOOT_HOVER_HEIGHT: f32 = 4.0; // Pickup models float this high over their drop point
+const LOOT_FADE_TIME: f32 = 2.0; // Seconds a pickup spends fading out before it expires
+const MAGNET_CLOSE_BOOST: f32 = 3.0; // Pickups right next to the player are pulled this much faster again
+const VACUUM_PULL: f32 = 600.0; // Pull speed when everything is being collected
+const WEAPON_CHEST_INTERVAL: u32 = 3; // Every third wave's chest holds a weapon crate
 
 pub struct LootPlugin;
 
 impl Plugin for LootPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Update, (
-                loot_pickup_system,
+            .init_resource::<LootTables>()
+            .init_resource::<LootPity>()
+            .add_systems(Startup, (load_loot_tables, setup_loot_assets))
+            .add_systems(FixedUpdate, (
+                enemy_loot_drop_system,
+                experience_orb_drop_system,
+                merge_experience_orbs_system,
                 loot_magnet_system,
+                loot_pickup_system,
+                loot_expiry_system,
+            ).chain()
+             .in_set(GameplaySet::Loot)
+             .run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                animate_loot_system,
+                fade_loot_system,
             ).run_if(in_state(GameState::Playing)));
     }
 }
@@ -18,63 +47,357 @@ impl Plugin for LootPlugin {
 #[derive(Component)]
 pub struct Loot {
     pub loot_type: LootType,
+    pub rarity: Rarity,
     pub value: f32,
     pub magnetic: bool,
     pub pickup_range: f32,
+    pub age: f32, // Seconds since it dropped, expires at GameConfig.loot_lifetime
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// The bobbing, spinning model under a pickup. Kept on a child so the animation never touches
+// the simulated transform.
+#[derive(Component)]
+pub struct LootVisual {
+    pub phase: f32,
+    pub faded_material: bool, // Has its own copy of the material to fade out
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LootType {
     Experience,
     Health,
     Shield,
-    Weapon,
-    PowerUp,
+    Credits, // Value is the number of credits
+    Weapon, // Unlocks a new weapon or levels up an owned one
+    PowerUp(PowerUpType), // Value scales the power-up's duration
 }
 
 impl Loot {
     pub fn new(loot_type: LootType, value: f32) -> Self {
         Self {
             loot_type,
+            rarity: Rarity::Common,
             value,
             magnetic: true,
             pickup_range: 30.0,
+            age: 0.0,
+        }
+    }
+}
+
+impl LootType {
+    pub fn color(&self) -> Color {
+        match self {
+            LootType::Experience => Color::rgb(0.3, 1.0, 0.4),
+            LootType::Health => Color::rgb(1.0, 0.25, 0.3),
+            LootType::Shield => Color::rgb(0.3, 0.7, 1.0),
+            LootType::Credits => Color::rgb(1.0, 0.85, 0.2),
+            LootType::Weapon => Color::rgb(1.0, 0.6, 0.2),
+            LootType::PowerUp(power_up) => power_up.color(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct LootAssets {
+    pub experience: LootModel,
+    pub health: LootModel,
+    pub shield: LootModel,
+    pub credits: LootModel,
+    pub weapon: LootModel,
+    pub power_ups: Vec<(PowerUpType, LootModel)>, // One colour per power-up
+}
+
+pub struct LootModel {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl LootAssets {
+    pub fn model(&self, loot_type: &LootType) -> &LootModel {
+        match loot_type {
+            LootType::Experience => &self.experience,
+            LootType::Health => &self.health,
+            LootType::Shield => &self.shield,
+            LootType::Credits => &self.credits,
+            LootType::Weapon => &self.weapon,
+            LootType::PowerUp(power_up) => self.power_ups
+                .iter()
+                .find(|(kind, _)| kind == power_up)
+                .map(|(_, model)| model)
+                .expect("a loot model for every power-up"),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: [Rarity; 4] = [Rarity::Common, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+    
+    // Multiplies the value of a pickup dropped at this rarity
+    pub fn value_multiplier(&self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Rare => 1.5,
+            Rarity::Epic => 2.5,
+            Rarity::Legendary => 4.0,
+        }
+    }
+    
+    // Colour and brightness of the light a pickup gives off. Common drops come in by the
+    // hundred, so they get no light and only glow through their emissive material.
+    pub fn glow(&self) -> (Color, f32) {
+        match self {
+            Rarity::Common => (Color::rgb(0.8, 0.8, 0.8), 0.0),
+            Rarity::Rare => (Color::rgb(0.2, 0.5, 1.0), 500.0),
+            Rarity::Epic => (Color::rgb(0.7, 0.25, 1.0), 1000.0),
+            Rarity::Legendary => (Color::rgb(1.0, 0.6, 0.1), 2000.0),
+        }
+    }
+}
+
+// Per-enemy drop tables, loaded from config/loot_tables.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct LootTables {
+    pub rarity_weights: RarityWeights,
+    // Each wave past the first multiplies the better tiers' weights by this much more
+    // (Rare once, Epic twice, Legendary three times), as does GameConfig.loot_chance_multiplier
+    pub rarity_scaling_per_wave: f32,
+    // Survival mode has no waves, so every this many seconds survived counts as one
+    #[serde(default = "default_survival_seconds_per_wave")]
+    pub survival_seconds_per_wave: f32,
+    // Kills in a row without a drop before the next kill is guaranteed one (0 turns it off)
+    pub pity_kills: u32,
+    pub tables: Vec<DropTable>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RarityWeights {
+    pub common: f32,
+    pub rare: f32,
+    pub epic: f32,
+    pub legendary: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DropTable {
+    pub enemy_type: EnemyType,
+    #[serde(default = "default_drop_rolls")]
+    pub rolls: u32, // Pickups per drop
+    #[serde(default)]
+    pub guaranteed: bool, // Always drops, whatever the enemy's loot chance
+    #[serde(default)]
+    pub min_rarity: Rarity,
+    pub entries: Vec<DropEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DropEntry {
+    pub loot_type: LootType,
+    pub weight: f32,
+    pub value: f32, // Before the rarity multiplier
+}
+
+fn default_drop_rolls() -> u32 {
+    1
+}
+
+fn default_survival_seconds_per_wave() -> f32 {
+    60.0
+}
+
+// Fallback for when config/loot_tables.ron can't be read: every enemy drops a little extra
+// experience and nothing else. The real tuning lives in the RON file.
+impl Default for LootTables {
+    fn default() -> Self {
+        let enemy_types = [EnemyType::Chaser, EnemyType::Swarm, EnemyType::Shooter, EnemyType::Tank, EnemyType::Elite, EnemyType::Boss];
+        
+        Self {
+            rarity_weights: RarityWeights {
+                common: 1.0,
+                rare: 0.0,
+                epic: 0.0,
+                legendary: 0.0,
+            },
+            rarity_scaling_per_wave: 0.0,
+            survival_seconds_per_wave: default_survival_seconds_per_wave(),
+            pity_kills: 0,
+            tables: enemy_types
+                .into_iter()
+                .map(|enemy_type| DropTable {
+                    enemy_type,
+                    rolls: default_drop_rolls(),
+                    guaranteed: false,
+                    min_rarity: Rarity::Common,
+                    entries: vec![DropEntry { loot_type: LootType::Experience, weight: 1.0, value: 10.0 }],
+                })
+                .collect(),
+        }
+    }
+}
+
+impl LootTables {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let tables_str = std::fs::read_to_string("config/loot_tables.ron")?;
+        let tables: LootTables = ron::de::from_str(&tables_str)?;
+        Ok(tables)
+    }
+    
+    pub fn table(&self, enemy_type: &EnemyType) -> Option<&DropTable> {
+        self.tables.iter().find(|table| table.enemy_type == *enemy_type)
+    }
+    
+    /// The wave survival mode's rarity scaling treats `elapsed` seconds as
+    pub fn survival_wave(&self, elapsed: f32) -> u32 {
+        1 + (elapsed / self.survival_seconds_per_wave.max(1.0)) as u32
+    }
+    
+    /// Rolls a rarity tier, with the better tiers more likely on later waves and with a higher
+    /// `loot_chance_multiplier`
+    pub fn roll_rarity(&self, wave: u32, loot_chance_multiplier: f32, rng: &mut impl Rng) -> Rarity {
+        let scaling = (1.0 + wave.saturating_sub(1) as f32 * self.rarity_scaling_per_wave) * loot_chance_multiplier.max(0.0);
+        let weights = &self.rarity_weights;
+        let weighted = [
+            (Rarity::Common, weights.common),
+            (Rarity::Rare, weights.rare * scaling),
+            (Rarity::Epic, weights.epic * scaling.powi(2)),
+            (Rarity::Legendary, weights.legendary * scaling.powi(3)),
+        ];
+        
+        let total: f32 = weighted.iter().map(|(_, weight)| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return Rarity::Common;
+        }
+        
+        let mut roll = rng.gen_range(0.0..total);
+        for (rarity, weight) in weighted {
+            let weight = weight.max(0.0);
+            if roll < weight {
+                return rarity;
+            }
+            roll -= weight;
+        }
+        Rarity::Common
+    }
+}
+
+impl DropTable {
+    pub fn pick_entry(&self, rng: &mut impl Rng) -> Option<&DropEntry> {
+        let total: f32 = self.entries.iter().map(|entry| entry.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        
+        let mut roll = rng.gen_range(0.0..total);
+        for entry in &self.entries {
+            if roll < entry.weight {
+                return Some(entry);
+            }
+            roll -= entry.weight;
+        }
+        self.entries.last()
+    }
+}
+
+// Kills since the last drop, for the pity timer
+#[derive(Resource, Default)]
+pub struct LootPity {
+    pub dry_kills: u32,
+}
+
 pub fn spawn_loot(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
+    loot_assets: &LootAssets,
     loot_type: LootType,
+    rarity: Rarity,
     position: Vec3,
     value: f32,
-) {
-    let (texture_path, scale) = match loot_type {
-        LootType::Experience => ("sprites/loot_exp.png", 0.3),
-        LootType::Health => ("sprites/loot_health.png", 0.4),
-        LootType::Shield => ("sprites/loot_shield.png", 0.4),
-        LootType::Weapon => ("sprites/loot_weapon.png", 0.5),
-        LootType::PowerUp => ("sprites/loot_powerup.png", 0.4),
-    };
+) -> Entity {
+    let model = loot_assets.model(&loot_type);
+    let scale = if loot_type == LootType::Experience { experience_gem_scale(value) } else { 1.0 };
+    let (glow_color, glow_intensity) = rarity.glow();
     
     commands.spawn((
-        SpriteBundle {
-            texture: asset_server.load(texture_path),
-            transform: Transform::from_translation(position)
-                .with_scale(Vec3::splat(scale)),
-            ..default()
+        SpatialBundle::from_transform(Transform::from_translation(position)),
+        Loot {
+            rarity,
+            ..Loot::new(loot_type, value)
         },
-        Loot::new(loot_type, value),
+        InterpolatedTransform::default(),
         GameEntity,
-        Name::new("Loot"),
-    ));
+        Name::new(format!("Loot_{:?}", rarity)),
+    )).with_children(|parent| {
+        parent.spawn((
+            PbrBundle {
+                mesh: model.mesh.clone(),
+                material: model.material.clone(),
+                transform: Transform::from_xyz(0.0, LOOT_HOVER_HEIGHT, 0.0).with_scale(Vec3::splat(scale)),
+                ..default()
+            },
+            LootVisual {
+                // Offset by position so neighbouring pickups don't bob in step
+                phase: position.x * 0.05 + position.z * 0.07,
+                faded_material: false,
+            },
+        ));
+        if glow_intensity > 0.0 {
+            parent.spawn(PointLightBundle {
+                point_light: PointLight {
+                    color: glow_color,
+                    intensity: glow_intensity,
+                    range: 30.0,
+                    shadows_enabled: false,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, LOOT_HOVER_HEIGHT, 0.0),
+                ..default()
+            });
+        }
+    }).id()
+}
+
+// Wave-clear reward - a burst of pickups scattered around `position`
+pub fn spawn_loot_chest(
+    commands: &mut Commands,
+    loot_assets: &LootAssets,
+    position: Vec3,
+    wave: u32,
+) {
+    let mut contents = vec![
+        (LootType::Health, 30.0),
+        (LootType::Shield, 30.0),
+        (LootType::Experience, wave as f32 * 10.0),
+    ];
+    if wave.is_multiple_of(WEAPON_CHEST_INTERVAL) {
+        contents.push((LootType::Weapon, 1.0));
+    }
+    
+    let count = contents.len();
+    for (i, (loot_type, value)) in contents.into_iter().enumerate() {
+        let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+        let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * 25.0;
+        spawn_loot(commands, loot_assets, loot_type, Rarity::Rare, position + offset, value);
+    }
 }
 
-fn loot_pickup_system(
+#[allow(clippy::too_many_arguments)]
+pub fn loot_pickup_system(
     mut commands: Commands,
+    catalogue: Res<UpgradeCatalogue>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
     loot_query: Query<(Entity, &Transform, &Loot)>,
     mut player_query: Query<(&Transform, &mut Player), Without<Loot>>,
+    mut game_rng: ResMut<GameRng>,
+    mut power_up_events: EventWriter<PowerUpCollectedEvent>,
+    mut weapon_events: EventWriter<WeaponGainedEvent>,
 ) {
     if let Ok((player_transform, mut player)) = player_query.get_single_mut() {
         for (loot_entity, loot_transform, loot) in loot_query.iter() {
@@ -83,7 +406,9 @@ fn loot_pickup_system(
             if distance <= loot.pickup_range {
                 match loot.loot_type {
                     LootType::Experience => {
-                        player.add_experience(loot.value);
+                        if player.add_experience(loot.value) {
+                            info!("Player leveled up to level {}!", player.level);
+                        }
                     }
                     LootType::Health => {
                         player.health = (player.health + loot.value).min(player.max_health);
@@ -91,38 +416,348 @@ fn loot_pickup_system(
                     LootType::Shield => {
                         player.shields = (player.shields + loot.value).min(player.max_shields);
                     }
+                    LootType::Credits => {
+                        player.credits += loot.value.round() as u32;
+                    }
                     LootType::Weapon => {
-                        // TODO: Implement weapon pickup
+                        // A ready evolution comes before anything else, otherwise it picks any
+                        // weapon that isn't maxed out yet, owned or not
+                        let evolution = upgrade_manager.ready_evolutions(&catalogue, &player).first().copied();
+                        let options = player.weapon_crate_options();
+                        if let Some(recipe) = evolution {
+                            // Goes through the upgrade manager so it counts as taken, same as picking it on level-up
+                            let weapon_type = recipe.evolves_into.clone();
+                            let upgrade_type = UpgradeType::EvolveWeapon { from: recipe.weapon.clone(), into: weapon_type.clone() };
+                            upgrade_manager.apply_upgrade(&Upgrade::new(upgrade_type, 1), &mut player);
+                            if let Some(weapon) = player.arsenal.iter().find(|weapon| weapon.weapon_type == weapon_type) {
+                                info!("Weapon crate: {} evolved into {}", recipe.weapon.name(), weapon_type.name());
+                                weapon_events.send(WeaponGainedEvent { weapon_type, level: weapon.level });
+                            }
+                        } else if options.is_empty() {
+                            info!("Weapon crate: arsenal is fully upgraded");
+                        } else {
+                            let weapon_type = options[game_rng.loot().gen_range(0..options.len())].clone();
+                            if let Some(level) = player.add_weapon(weapon_type.clone()) {
+                                info!("Weapon crate: {} level {}", weapon_type.name(), level);
+                                weapon_events.send(WeaponGainedEvent { weapon_type, level });
+                            }
+                        }
                     }
-                    LootType::PowerUp => {
-                        // TODO: Implement power-up effects
+                    LootType::PowerUp(power_up) => {
+                        power_up_events.send(PowerUpCollectedEvent {
+                            power_up,
+                            duration_scale: loot.value,
+                        });
                     }
                 }
                 
-                commands.entity(loot_entity).despawn();
+                commands.entity(loot_entity).despawn_recursive();
             }
         }
     }
 }
 
+fn load_loot_tables(mut loot_tables: ResMut<LootTables>) {
+    match LootTables::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded {} loot tables", loaded.tables.len());
+            *loot_tables = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/loot_tables.ron ({}), enemies will only drop experience", err);
+        }
+    }
+}
+
+fn setup_loot_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut model = |mesh: Mesh, loot_type: LootType| {
+        let color = loot_type.color();
+        LootModel {
+            mesh: meshes.add(mesh),
+            material: materials.add(StandardMaterial {
+                base_color: color,
+                emissive: color * 0.8,
+                perceptual_roughness: 0.3,
+                alpha_mode: AlphaMode::Blend, // So pickups can fade out
+                ..default()
+            }),
+        }
+    };
+    
+    commands.insert_resource(LootAssets {
+        experience: model(
+            Mesh::try_from(shape::Icosphere { radius: 3.0, subdivisions: 0 }).expect("valid icosphere"),
+            LootType::Experience,
+        ),
+        health: model(Mesh::from(shape::Cube { size: 6.0 }), LootType::Health),
+        shield: model(
+            Mesh::from(shape::Torus { radius: 4.5, ring_radius: 1.5, ..default() }),
+            LootType::Shield,
+        ),
+        credits: model(Mesh::from(shape::Cylinder { radius: 3.0, height: 1.0, ..default() }), LootType::Credits),
+        weapon: model(Mesh::from(shape::Box::new(9.0, 3.0, 4.0)), LootType::Weapon),
+        power_ups: PowerUpType::ALL
+            .iter()
+            .map(|&power_up| (power_up, model(
+                Mesh::from(shape::Capsule { radius: 2.5, depth: 4.0, ..default() }),
+                LootType::PowerUp(power_up),
+            )))
+            .collect(),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn enemy_loot_drop_system(
+    mut commands: Commands,
+    loot_assets: Res<LootAssets>,
+    config: Res<GameConfig>,
+    loot_tables: Res<LootTables>,
+    game_mode: Res<GameMode>,
+    wave_manager: Res<WaveManager>,
+    survival_run: Res<SurvivalRun>,
+    mut pity: ResMut<LootPity>,
+    mut game_rng: ResMut<GameRng>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    let rng = game_rng.loot();
+    let wave = match *game_mode {
+        GameMode::Waves => wave_manager.current_wave,
+        GameMode::Survival => loot_tables.survival_wave(survival_run.elapsed),
+    };
+    
+    for event in killed_events.read() {
+        let Some(table) = loot_tables.table(&event.enemy_type) else {
+            continue;
+        };
+        
+        let drop_chance = (event.loot_chance * config.loot_chance_multiplier).clamp(0.0, 1.0);
+        let pity_drop = loot_tables.pity_kills > 0 && pity.dry_kills + 1 >= loot_tables.pity_kills;
+        if !table.guaranteed && !pity_drop && !rng.gen_bool(drop_chance as f64) {
+            pity.dry_kills += 1;
+            continue;
+        }
+        pity.dry_kills = 0;
+        
+        for i in 0..table.rolls {
+            let Some(entry) = table.pick_entry(rng) else {
+                break;
+            };
+            let rarity = loot_tables
+                .roll_rarity(wave, config.loot_chance_multiplier, rng)
+                .max(table.min_rarity);
+            
+            // Spread multi-drops out so they don't stack on one spot
+            let offset = if table.rolls > 1 {
+                let angle = i as f32 / table.rolls as f32 * std::f32::consts::TAU;
+                Vec3::new(angle.cos(), 0.0, angle.sin()) * 20.0
+            } else {
+                Vec3::ZERO
+            };
+            
+            spawn_loot(
+                &mut commands,
+                &loot_assets,
+                entry.loot_type.clone(),
+                rarity,
+                event.position + offset,
+                entry.value * rarity.value_multiplier(),
+            );
+        }
+    }
+}
+
+// Experience is collected from orbs rather than granted on kill
+fn experience_orb_drop_system(
+    mut commands: Commands,
+    loot_assets: Res<LootAssets>,
+    config: Res<GameConfig>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    for event in killed_events.read() {
+        spawn_loot(
+            &mut commands,
+            &loot_assets,
+            LootType::Experience,
+            Rarity::Common,
+            event.position,
+            event.experience_value * config.experience_multiplier,
+        );
+    }
+}
+
+// Experience orb models grow with the experience they hold
+pub fn experience_gem_scale(value: f32) -> f32 {
+    (value / 20.0).cbrt().clamp(1.0, 3.0)
+}
+
+// Once there are too many experience orbs, nearby ones merge into a single bigger gem so late
+// waves don't pile up hundreds of entities
+pub fn merge_experience_orbs_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut loot_query: Query<(Entity, &Transform, &mut Loot, &Children)>,
+    mut visual_query: Query<&mut Transform, (With<LootVisual>, Without<Loot>)>,
+) {
+    let mut orbs: Vec<(Entity, Vec3)> = loot_query
+        .iter()
+        .filter(|(_, _, loot, _)| loot.loot_type == LootType::Experience)
+        .map(|(entity, transform, _, _)| (entity, transform.translation))
+        .collect();
+    if orbs.len() <= config.max_experience_orbs {
+        return;
+    }
+    orbs.sort_by_key(|(entity, _)| *entity);
+    
+    // Each pass merges everything within the radius into the first orb it finds, widening the
+    // radius until enough orbs are gone
+    let mut merged = vec![false; orbs.len()];
+    let mut remaining = orbs.len();
+    let mut radius = config.orb_merge_radius;
+    for _ in 0..4 {
+        for i in 0..orbs.len() {
+            if merged[i] {
+                continue;
+            }
+            
+            let (gem, position) = orbs[i];
+            let (mut value, mut age) = loot_query.get(gem).map(|(_, _, loot, _)| (loot.value, loot.age)).unwrap_or_default();
+            let mut absorbed_any = false;
+            for j in (i + 1)..orbs.len() {
+                if merged[j] || orbs[j].1.distance(position) > radius {
+                    continue;
+                }
+                if let Ok((orb, _, loot, _)) = loot_query.get(orbs[j].0) {
+                    value += loot.value;
+                    age = age.min(loot.age); // The gem lasts as long as its freshest orb would have
+                    commands.entity(orb).despawn_recursive();
+                    merged[j] = true;
+                    remaining -= 1;
+                    absorbed_any = true;
+                }
+            }
+            
+            if !absorbed_any {
+                continue;
+            }
+            if let Ok((_, _, mut loot, children)) = loot_query.get_mut(gem) {
+                loot.value = value;
+                loot.age = age;
+                let mut visuals = visual_query.iter_many_mut(children);
+                while let Some(mut visual) = visuals.fetch_next() {
+                    visual.scale = Vec3::splat(experience_gem_scale(value));
+                }
+            }
+        }
+        
+        if remaining <= config.max_experience_orbs {
+            break;
+        }
+        radius *= 2.0;
+    }
+}
+
 fn loot_magnet_system(
     time: Res<Time>,
+    wave_manager: Res<WaveManager>,
     mut loot_query: Query<(&mut Transform, &Loot)>,
-    player_query: Query<&Transform, (With<Player>, Without<Loot>)>,
+    player_query: Query<(&Transform, &Player), Without<Loot>>,
 ) {
-    if let Ok(player_transform) = player_query.get_single() {
+    if let Ok((player_transform, player)) = player_query.get_single() {
+        // Between waves, or with the Magnet power-up, everything on the field gets pulled in
+        let vacuum = !wave_manager.wave_active || player.buffs.loot_vacuum;
+        
         for (mut loot_transform, loot) in loot_query.iter_mut() {
-            if !loot.magnetic {
+            if !loot.magnetic && !vacuum {
                 continue;
             }
             
             let distance = player_transform.translation.distance(loot_transform.translation);
-            let magnet_range = 100.0;
+            if !vacuum && distance > player.magnet_range {
+                continue;
+            }
+            
+            // The pull gets stronger the closer the pickup gets
+            let closeness = if vacuum { 0.0 } else { 1.0 - distance / player.magnet_range.max(1.0) };
+            let base_pull = if vacuum { player.magnet_pull.max(VACUUM_PULL) } else { player.magnet_pull };
+            let magnet_speed = base_pull * (1.0 + MAGNET_CLOSE_BOOST * closeness);
+            
+            // Don't overshoot the player
+            let step = (magnet_speed * time.delta_seconds()).min(distance);
+            let direction = (player_transform.translation - loot_transform.translation).normalize_or_zero();
+            loot_transform.translation += direction * step;
+        }
+    }
+}
+
+// Pickups left lying around too long expire so they don't pile up
+pub fn loot_expiry_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut loot_query: Query<(Entity, &mut Loot)>,
+) {
+    for (entity, mut loot) in loot_query.iter_mut() {
+        loot.age += time.delta_seconds();
+        if loot.age >= config.loot_lifetime {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn animate_loot_system(
+    time: Res<Time>,
+    mut visual_query: Query<(&mut Transform, &LootVisual)>,
+) {
+    let elapsed = time.elapsed_seconds();
+    
+    for (mut transform, visual) in visual_query.iter_mut() {
+        let t = elapsed + visual.phase;
+        transform.translation.y = LOOT_HOVER_HEIGHT + (t * 3.0).sin() * 1.0;
+        transform.rotation = Quat::from_rotation_y(t * 2.0) * Quat::from_rotation_x(0.4);
+    }
+}
+
+fn fade_loot_system(
+    config: Res<GameConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    loot_query: Query<(&Loot, &Children)>,
+    mut visual_query: Query<(&mut LootVisual, &mut Handle<StandardMaterial>)>,
+    mut light_query: Query<&mut PointLight>,
+) {
+    for (loot, children) in loot_query.iter() {
+        let remaining = config.loot_lifetime - loot.age;
+        if remaining >= LOOT_FADE_TIME {
+            continue;
+        }
+        let alpha = (remaining / LOOT_FADE_TIME).clamp(0.0, 1.0);
+        
+        for &child in children.iter() {
+            // The glow dims along with the pickup
+            if let Ok(mut light) = light_query.get_mut(child) {
+                light.intensity = loot.rarity.glow().1 * alpha;
+            }
+            
+            let Ok((mut visual, mut material_handle)) = visual_query.get_mut(child) else {
+                continue;
+            };
+            
+            // Pickups share a material per type, so each one fades its own copy
+            if !visual.faded_material {
+                let Some(shared) = materials.get(&*material_handle).cloned() else {
+                    continue;
+                };
+                *material_handle = materials.add(shared);
+                visual.faded_material = true;
+            }
             
-            if distance <= magnet_range {
-                let direction = (player_transform.translation - loot_transform.translation).normalize();
-                let magnet_speed = 200.0;
-                loot_transform.translation += direction * magnet_speed * time.delta_seconds();
+            if let Some(material) = materials.get_mut(&*material_handle) {
+                let color = loot.loot_type.color();
+                material.base_color = color.with_a(alpha);
+                material.emissive = color * 0.8 * alpha;
             }
         }
     }
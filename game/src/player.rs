@@ -1,21 +1,38 @@
 //! Player system - handles player movement, shooting, and health
 use bevy::prelude::*;
 use bevy::input::mouse::MouseButton;
+use bevy::input::InputSystem;
 use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::{GameState, GameEntity};
+use crate::config::PlayerConfig;
+use crate::powerup::{ActiveBuff, BuffModifiers};
+use crate::simulation::{GameplaySet, InterpolatedTransform};
+use crate::status::{StatusEffect, StatusEffectType, StatusEffects};
+use crate::upgrade::{SpecialAbilities, DASH_DURATION, DASH_SPEED};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<PlayerAim>()
+            .add_event::<WeaponGainedEvent>()
+            .add_systems(PreUpdate, capture_player_aim_system.after(InputSystem))
             .add_systems(OnEnter(GameState::Playing), spawn_player)
-            .add_systems(Update, (
+            .add_systems(FixedUpdate, (
                 player_movement_system,
                 player_shooting_system,
                 player_stats_system,
                 update_projectiles_system,
-            ).run_if(in_state(GameState::Playing)));
+            ).chain()
+             .in_set(GameplaySet::Player)
+             .run_if(in_state(GameState::Playing)))
+            // Last in the tick, so a death wins over a level-up landing at the same time
+            .add_systems(FixedUpdate, player_death_system
+                .after(crate::upgrade::level_up_check_system)
+                .in_set(GameplaySet::Progression)
+                .run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -27,23 +44,43 @@ pub struct Player {
     pub shields: f32,
     pub max_shields: f32,
     pub special_energy: f32,
-    pub max_special_energy: f32,
+    pub max_special_energy: f32, // Spent on dashes
+    pub time_since_hit: f32, // Shields wait a while after a hit before they regenerate
     pub experience: f32,
     pub level: u32,
+    pub pending_level_ups: u32, // Level-ups still waiting for an upgrade pick
+    pub credits: u32, // Spent in the shop between waves
     
     // Movement
     pub speed: f32,
+    pub dash_remaining: f32, // Seconds left of the current dash
+    
+    // Loot magnet
+    pub magnet_range: f32,
+    pub magnet_pull: f32, // Pull speed at the edge of the range, faster closer in
     
     // Weapons
-    pub primary_weapon: WeaponType,
-    pub fire_rate: f32,
+    pub arsenal: Vec<PlayerWeapon>, // Every owned weapon fires when the player shoots
+    pub fire_rate: f32, // Shots per second, before each weapon's own fire rate
     pub last_shot_time: f32,
     pub damage_multiplier: f32,
+    pub on_hit_effects: Vec<StatusEffect>,
+    pub abilities: SpecialAbilities,
+    
+    // Combined modifiers of every running power-up, refreshed by the stat system
+    pub buffs: BuffModifiers,
     
     // State
     pub is_alive: bool,
 }
 
+// Cursor position relative to the window centre (Y up), or None when it's outside the window.
+// Kept as its own resource so replays can feed recorded aim back in.
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub struct PlayerAim {
+    pub cursor_offset: Option<Vec2>,
+}
+
 #[derive(Component)]
 pub struct PlayerMovement {
     pub velocity: Vec3,
@@ -53,25 +90,202 @@ pub struct PlayerMovement {
     pub current_direction: f32, // Angle in radians, 0 = forward (positive Z)
 }
 
-#[derive(Component)]
+pub const MAX_WEAPON_LEVEL: u32 = 5;
+
+// A weapon in the player's arsenal, on its own cooldown
+#[derive(Clone, Debug)]
 pub struct PlayerWeapon {
     pub weapon_type: WeaponType,
+    pub level: u32,
     pub last_shot: f32,
-    pub fire_rate: f32,
-    pub damage: f32,
+    pub evolved_from: Option<WeaponType>, // The base weapon, once this one has evolved
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl PlayerWeapon {
+    pub fn new(weapon_type: WeaponType) -> Self {
+        Self {
+            weapon_type,
+            level: 1,
+            last_shot: 0.0,
+            evolved_from: None,
+        }
+    }
+    
+    pub fn stats(&self) -> WeaponStats {
+        self.weapon_type.stats(self.level)
+    }
+    
+    /// Whether this is `weapon_type`, or what it evolved into
+    pub fn is(&self, weapon_type: &WeaponType) -> bool {
+        self.weapon_type == *weapon_type || self.evolved_from.as_ref() == Some(weapon_type)
+    }
+    
+    pub fn can_shoot(&self, current_time: f32, fire_rate: f32) -> bool {
+        current_time - self.last_shot >= 1.0 / (fire_rate * self.stats().fire_rate)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum WeaponType {
+    #[default]
     Blaster,
     Laser,
     Rocket,
     AoePulse,
+    // Evolutions, only reached through the recipes in config/upgrades.ron
+    PrismBeam,
+    ClusterMissiles,
 }
 
-impl Default for WeaponType {
-    fn default() -> Self {
-        WeaponType::Blaster
+// What a weapon does at one level
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeaponStats {
+    pub damage: f32,
+    pub fire_rate: f32, // Multiplies the player's fire rate
+    pub projectiles: u32,
+    pub spread: f32, // Angle the projectiles fan out over, in radians (TAU is a full ring)
+    pub pierce: u32, // Extra enemies each shot passes through
+    pub splash_radius: f32, // Explodes on hit when above 0
+}
+
+impl WeaponStats {
+    /// Directions of one volley aimed along `aim`
+    pub fn shot_directions(&self, aim: Vec3) -> Vec<Vec3> {
+        let count = self.projectiles.max(1);
+        (0..count)
+            .map(|i| {
+                let angle = if self.spread >= std::f32::consts::TAU {
+                    // A full ring, one shot still straight at the cursor
+                    i as f32 * self.spread / count as f32
+                } else if count > 1 {
+                    -self.spread * 0.5 + i as f32 * self.spread / (count - 1) as f32
+                } else {
+                    0.0
+                };
+                Quat::from_rotation_y(angle) * aim
+            })
+            .collect()
+    }
+}
+
+impl WeaponType {
+    pub const ALL: [WeaponType; 4] = [WeaponType::Blaster, WeaponType::Laser, WeaponType::Rocket, WeaponType::AoePulse];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponType::Blaster => "Blaster",
+            WeaponType::Laser => "Laser",
+            WeaponType::Rocket => "Rocket",
+            WeaponType::AoePulse => "Pulse",
+            WeaponType::PrismBeam => "Prism Beam",
+            WeaponType::ClusterMissiles => "Cluster Missiles",
+        }
+    }
+    
+    pub fn description(&self) -> &'static str {
+        match self {
+            WeaponType::Blaster => "Rapid shots that fan out as it levels up",
+            WeaponType::Laser => "Fast shocking bolts that pierce at higher levels",
+            WeaponType::Rocket => "Slow burning rockets that explode at higher levels",
+            WeaponType::AoePulse => "A ring of slowing pulses around the ship",
+            WeaponType::PrismBeam => "A fan of shocking beams that cut through whole packs",
+            WeaponType::ClusterMissiles => "Volleys of burning missiles with huge blasts",
+        }
+    }
+    
+    pub fn is_evolved(&self) -> bool {
+        matches!(self, WeaponType::PrismBeam | WeaponType::ClusterMissiles)
+    }
+    
+    // Evolved weapons don't level up, their stats are fixed
+    pub fn stats(&self, level: u32) -> WeaponStats {
+        let level = level.clamp(1, MAX_WEAPON_LEVEL);
+        let damage_scale = 1.0 + 0.25 * (level - 1) as f32; // +25% damage a level
+        
+        match self {
+            WeaponType::Blaster => WeaponStats {
+                damage: 20.0 * damage_scale,
+                fire_rate: 1.0,
+                projectiles: 1 + (level - 1) / 2, // Another shot at levels 3 and 5
+                spread: 0.25,
+                pierce: 0,
+                splash_radius: 0.0,
+            },
+            WeaponType::Laser => WeaponStats {
+                damage: 30.0 * damage_scale,
+                fire_rate: 0.6,
+                projectiles: 1,
+                spread: 0.0,
+                pierce: match level {
+                    1 | 2 => 0,
+                    3 | 4 => 1,
+                    _ => 3,
+                },
+                splash_radius: 0.0,
+            },
+            WeaponType::Rocket => WeaponStats {
+                damage: 45.0 * damage_scale,
+                fire_rate: 0.35,
+                projectiles: if level >= 5 { 2 } else { 1 },
+                spread: 0.2,
+                pierce: 0,
+                splash_radius: if level >= 3 { 60.0 + 20.0 * (level - 3) as f32 } else { 0.0 },
+            },
+            WeaponType::AoePulse => WeaponStats {
+                damage: 12.0 * damage_scale,
+                fire_rate: 0.4,
+                projectiles: 5 + level,
+                spread: std::f32::consts::TAU,
+                pierce: 0,
+                splash_radius: 0.0,
+            },
+            WeaponType::PrismBeam => WeaponStats {
+                damage: 60.0,
+                fire_rate: 0.8,
+                projectiles: 3,
+                spread: 0.3,
+                pierce: 6,
+                splash_radius: 0.0,
+            },
+            WeaponType::ClusterMissiles => WeaponStats {
+                damage: 70.0,
+                fire_rate: 0.45,
+                projectiles: 4,
+                spread: 0.5,
+                pierce: 0,
+                splash_radius: 120.0,
+            },
+        }
+    }
+    
+    /// What reaching `level` adds, for the pickup card
+    pub fn level_up_summary(&self, level: u32) -> String {
+        let (old, new) = (self.stats(level.saturating_sub(1)), self.stats(level));
+        let mut gains = vec![format!("+{:.0}% damage", (new.damage / old.damage - 1.0) * 100.0)];
+        
+        if new.projectiles > old.projectiles {
+            gains.push(format!("+{} projectiles", new.projectiles - old.projectiles));
+        }
+        if new.pierce > old.pierce {
+            gains.push(format!("pierces {} enemies", new.pierce));
+        }
+        if new.splash_radius > old.splash_radius {
+            gains.push(if old.splash_radius > 0.0 { "bigger explosions".to_string() } else { "explodes on hit".to_string() });
+        }
+        
+        gains.join(", ")
+    }
+    
+    // Status effect every shot of this weapon applies on hit
+    pub fn on_hit_effect(&self) -> Option<StatusEffect> {
+        match self {
+            WeaponType::Blaster => None,
+            WeaponType::Laser => Some(StatusEffect::new(StatusEffectType::Shock)),
+            WeaponType::Rocket => Some(StatusEffect::new(StatusEffectType::Burn)),
+            WeaponType::AoePulse => Some(StatusEffect::new(StatusEffectType::Slow)),
+            WeaponType::PrismBeam => Some(StatusEffect::new(StatusEffectType::Shock)),
+            WeaponType::ClusterMissiles => Some(StatusEffect::new(StatusEffectType::Burn)),
+        }
     }
 }
 
@@ -90,18 +304,33 @@ impl Player {
             max_shields: 50.0,
             special_energy: 100.0,
             max_special_energy: 100.0,
+            time_since_hit: 0.0,
             experience: 0.0,
             level: 1,
+            pending_level_ups: 0,
+            credits: 0,
             speed: 300.0, // pixels per second
-            primary_weapon: WeaponType::Blaster,
+            dash_remaining: 0.0,
+            magnet_range: 100.0,
+            magnet_pull: 200.0,
+            arsenal: vec![PlayerWeapon::new(WeaponType::Blaster)],
             fire_rate: 5.0, // shots per second
             last_shot_time: 0.0,
             damage_multiplier: 1.0,
+            on_hit_effects: Vec::new(),
+            abilities: SpecialAbilities::default(),
+            buffs: BuffModifiers::default(),
             is_alive: true,
         }
     }
     
     pub fn take_damage(&mut self, damage: f32) {
+        // Dashing ships can't be hit either
+        if self.buffs.invulnerable || self.dash_remaining > 0.0 {
+            return;
+        }
+        
+        self.time_since_hit = 0.0;
         if self.shields > 0.0 {
             let shield_damage = damage.min(self.shields);
             self.shields -= shield_damage;
@@ -125,17 +354,70 @@ impl Player {
         if self.experience >= exp_needed {
             self.level += 1;
             self.experience -= exp_needed;
+            self.pending_level_ups += 1;
             true // Level up occurred
         } else {
             false
         }
     }
     
-    pub fn can_shoot(&self, current_time: f32) -> bool {
-        current_time - self.last_shot_time >= 1.0 / self.fire_rate
+    /// A weapon's stats with the player's Piercing and Explosive Shots added on
+    pub fn weapon_stats(&self, weapon: &PlayerWeapon) -> WeaponStats {
+        let mut stats = weapon.stats();
+        stats.pierce += self.abilities.extra_pierce();
+        stats.splash_radius += self.abilities.splash_radius();
+        stats
+    }
+    
+    /// Unlocks `weapon_type` or levels it up if it's already owned. Returns its new level, or
+    /// None when it's already at max level or has evolved.
+    pub fn add_weapon(&mut self, weapon_type: WeaponType) -> Option<u32> {
+        match self.arsenal.iter_mut().find(|weapon| weapon.is(&weapon_type)) {
+            Some(weapon) if weapon.level >= MAX_WEAPON_LEVEL => None,
+            Some(weapon) => {
+                weapon.level += 1;
+                Some(weapon.level)
+            }
+            None => {
+                self.arsenal.push(PlayerWeapon::new(weapon_type));
+                Some(1)
+            }
+        }
+    }
+    
+    /// Weapons a crate could still unlock or level up
+    pub fn weapon_crate_options(&self) -> Vec<WeaponType> {
+        WeaponType::ALL
+            .into_iter()
+            .filter(|weapon_type| {
+                self.arsenal
+                    .iter()
+                    .find(|weapon| weapon.is(weapon_type))
+                    .is_none_or(|weapon| weapon.level < MAX_WEAPON_LEVEL)
+            })
+            .collect()
+    }
+    
+    /// Swaps a max-level `from` for its evolution `into`. False if there's no such weapon to evolve.
+    pub fn evolve_weapon(&mut self, from: &WeaponType, into: WeaponType) -> bool {
+        match self.arsenal.iter_mut().find(|weapon| weapon.weapon_type == *from && weapon.level >= MAX_WEAPON_LEVEL) {
+            Some(weapon) => {
+                weapon.evolved_from = Some(weapon.weapon_type.clone());
+                weapon.weapon_type = into;
+                true
+            }
+            None => false,
+        }
     }
 }
 
+// A weapon was unlocked (level 1) or levelled up
+#[derive(Event)]
+pub struct WeaponGainedEvent {
+    pub weapon_type: WeaponType,
+    pub level: u32,
+}
+
 // Projectile system
 #[derive(Component)]
 pub struct Projectile {
@@ -143,16 +425,37 @@ pub struct Projectile {
     pub damage: f32,
     pub lifetime: f32,
     pub max_lifetime: f32,
+    pub on_hit_effects: Vec<StatusEffect>,
+    pub owner: Option<Entity>,
+    pub pierce: u32, // Enemies it can still pass through
+    pub splash_radius: f32,
+    pub hits: Vec<Entity>, // Enemies it already passed through
 }
 
 // Systems
-fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
-    info!("Spawning player");
+fn spawn_player(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    existing: Query<(), With<Player>>,
+) {
+    // Resuming from pause or a level-up re-enters Playing; keep the ship we already have
+    if !existing.is_empty() {
+        return;
+    }
     
+    info!("Spawning player");
+    spawn_player_ship(&mut commands, &asset_server, Vec3::new(0.0, 0.5, 0.0)); // Lift off ground
+}
+
+pub fn spawn_player_ship(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+) -> Entity {
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/player_fighter.gltf#Scene0"),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0) // Lift off ground
+            transform: Transform::from_translation(position)
                 .with_scale(Vec3::splat(0.5)), // Scale down the fighter model
             ..default()
         },
@@ -167,6 +470,8 @@ fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
         },
         LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y, // Lock Y rotation and movement
         Player::new(),
+        StatusEffects::default(),
+        InterpolatedTransform::default(),
         PlayerMovement {
             velocity: Vec3::ZERO,
             max_speed: 300.0,
@@ -174,28 +479,24 @@ fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
             turn_speed: 3.0,
             current_direction: 0.0, // Start facing forward (positive Z)
         },
-        PlayerWeapon {
-            weapon_type: WeaponType::Blaster,
-            last_shot: 0.0,
-            fire_rate: 5.0,
-            damage: 20.0,
-        },
         GameEntity,
         Name::new("Player"),
-    ));
+    )).id()
 }
 
 fn player_movement_system(
     keyboard_input: Res<Input<KeyCode>>,
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut PlayerMovement, &mut Velocity, &Player)>,
+    mut query: Query<(&mut Transform, &mut PlayerMovement, &mut Velocity, &mut Player, Option<&StatusEffects>)>,
 ) {
-    for (mut transform, mut movement, mut velocity, player) in query.iter_mut() {
+    for (mut transform, mut movement, mut velocity, mut player, status) in query.iter_mut() {
         if !player.is_alive {
             continue;
         }
         
-        let dt = time.delta_seconds();
+        // Slow and freeze scale down how fast the ship can turn and thrust
+        let speed_multiplier = status.map_or(1.0, |s| s.speed_multiplier());
+        let dt = time.delta_seconds() * speed_multiplier;
         
         // Handle turning with A and D keys
         if keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left) {
@@ -232,9 +533,21 @@ fn player_movement_system(
             velocity.linvel -= reverse_thrust;
         }
         
-        // Limit maximum speed
-        if velocity.linvel.length() > movement.max_speed {
-            velocity.linvel = velocity.linvel.normalize() * movement.max_speed;
+        // Dash along the ship's heading while Shift is held and there's energy for it
+        player.dash_remaining = (player.dash_remaining - time.delta_seconds()).max(0.0);
+        if let Some(cost) = player.abilities.dash_cost() {
+            let dashing = player.dash_remaining > 0.0;
+            if keyboard_input.pressed(KeyCode::ShiftLeft) && !dashing && player.special_energy >= cost {
+                player.special_energy -= cost;
+                player.dash_remaining = DASH_DURATION;
+                velocity.linvel = forward * DASH_SPEED;
+            }
+        }
+        
+        // Limit maximum speed, except mid-dash
+        let max_speed = movement.max_speed * speed_multiplier;
+        if player.dash_remaining <= 0.0 && velocity.linvel.length() > max_speed {
+            velocity.linvel = velocity.linvel.normalize_or_zero() * max_speed;
         }
         
         // Update ship rotation to face movement direction
@@ -256,17 +569,27 @@ fn player_movement_system(
     }
 }
 
+pub fn capture_player_aim_system(
+    windows: Query<&Window>,
+    mut aim: ResMut<PlayerAim>,
+) {
+    if let Ok(window) = windows.get_single() {
+        aim.cursor_offset = window.cursor_position().map(|cursor_pos| {
+            Vec2::new(cursor_pos.x - window.width() * 0.5, window.height() * 0.5 - cursor_pos.y)
+        });
+    }
+}
+
 fn player_shooting_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mouse_input: Res<Input<MouseButton>>,
     keyboard_input: Res<Input<KeyCode>>,
     time: Res<Time>,
-    windows: Query<&Window>,
-    camera_query: Query<(&Camera, &GlobalTransform), With<crate::MainCamera>>,
-    mut player_query: Query<(&Transform, &mut PlayerWeapon, &mut Player)>,
+    aim: Res<PlayerAim>,
+    mut player_query: Query<(Entity, &Transform, &mut Player)>,
 ) {
-    for (player_transform, mut weapon, mut player) in player_query.iter_mut() {
+    for (player_entity, player_transform, mut player) in player_query.iter_mut() {
         if !player.is_alive {
             continue;
         }
@@ -277,59 +600,78 @@ fn player_shooting_system(
         let wants_to_shoot = mouse_input.pressed(MouseButton::Left) || 
                            keyboard_input.pressed(KeyCode::Space);
         
-        if wants_to_shoot && player.can_shoot(current_time) {
-            // Get mouse position for aiming
-            if let Ok(window) = windows.get_single() {
-                if let Ok((camera, camera_transform)) = camera_query.get_single() {
-                    if let Some(cursor_pos) = window.cursor_position() {
-                        // Convert screen coordinates to world coordinates
-                        let window_size = Vec2::new(window.width(), window.height());
-                        
-                        // Convert screen position to world position
-                        let ndc = (cursor_pos / window_size) * 2.0 - Vec2::ONE;
-                        let ndc = Vec3::new(ndc.x, -ndc.y, 0.0);
-                        
-                        // Simple world position calculation for 2D
-                        let world_pos = Vec3::new(
-                            ndc.x * window_size.x * 0.5,
-                            ndc.y * window_size.y * 0.5,
-                            0.0
-                        );
-                        
-                        // Calculate shooting direction
-                        let direction = (world_pos - player_transform.translation).normalize();
-                        
-                        // Spawn projectile
-                        spawn_projectile(
-                            &mut commands,
-                            &asset_server,
-                            player_transform.translation,
-                            direction,
-                            &weapon.weapon_type,
-                            weapon.damage,
-                        );
-                        
-                        // Update last shot time
-                        weapon.last_shot = current_time;
-                        player.last_shot_time = current_time;
-                    }
+        if !wants_to_shoot {
+            continue;
+        }
+        
+        // Get mouse position for aiming
+        let Some(cursor_offset) = aim.cursor_offset else {
+            continue;
+        };
+        
+        // The camera sits straight above the ship with screen-up along -Z, so the
+        // cursor's offset from the centre is a direction on the ground plane
+        let direction = Vec3::new(cursor_offset.x, 0.0, -cursor_offset.y).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            continue;
+        }
+        
+        let fire_rate = player.fire_rate * player.buffs.fire_rate_multiplier;
+        let damage_multiplier = player.damage_multiplier * player.buffs.damage_multiplier * player.abilities.shot_damage_scale();
+        let offsets = player.abilities.shot_offsets();
+        
+        // Every weapon in the arsenal fires on its own cooldown
+        for index in 0..player.arsenal.len() {
+            if !player.arsenal[index].can_shoot(current_time, fire_rate) {
+                continue;
+            }
+            
+            let mut stats = player.weapon_stats(&player.arsenal[index]);
+            stats.damage *= damage_multiplier;
+            for shot_direction in stats.shot_directions(direction) {
+                // Double Shot copies fly side by side
+                let side = Vec3::Y.cross(shot_direction).normalize_or_zero();
+                for offset in &offsets {
+                    spawn_projectile(
+                        &mut commands,
+                        &asset_server,
+                        player_transform.translation + side * *offset,
+                        shot_direction,
+                        &player.arsenal[index].weapon_type,
+                        &stats,
+                        &player.on_hit_effects,
+                        player_entity,
+                    );
                 }
             }
+            
+            // Update last shot time
+            player.arsenal[index].last_shot = current_time;
+            player.last_shot_time = current_time;
         }
     }
 }
 
 fn player_stats_system(
     time: Res<Time>,
-    mut query: Query<&mut Player>,
+    config: Res<PlayerConfig>,
+    mut query: Query<(&mut Player, Option<&Children>)>,
+    buff_query: Query<&ActiveBuff>,
 ) {
-    for mut player in query.iter_mut() {
+    for (mut player, children) in query.iter_mut() {
         let dt = time.delta_seconds();
         
-        // Regenerate shields after delay
-        if player.shields < player.max_shields {
-            // Simple shield regen - could be made more sophisticated
-            player.shields = (player.shields + 10.0 * dt).min(player.max_shields);
+        // Fold every running buff into the ship's modifiers
+        player.buffs = children
+            .into_iter()
+            .flat_map(|children| buff_query.iter_many(children))
+            .fold(BuffModifiers::default(), |total, buff| total.combine(&buff.total_modifiers()));
+        
+        // Regenerate shields after delay, both sped up by Shield Regeneration
+        let regen_multiplier = player.abilities.shield_regen_multiplier();
+        player.time_since_hit += dt;
+        if player.shields < player.max_shields && player.time_since_hit >= config.shield_regen_delay / regen_multiplier {
+            player.shields = (player.shields + config.shield_regen_rate * regen_multiplier * dt).min(player.max_shields);
         }
         
         // Regenerate special energy
@@ -339,19 +681,34 @@ fn player_stats_system(
     }
 }
 
-fn spawn_projectile(
+fn player_death_system(
+    player_query: Query<&Player>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if player_query.iter().any(|player| !player.is_alive) {
+        info!("Player destroyed - game over");
+        next_state.set(GameState::GameOver);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_projectile(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
     position: Vec3,
     direction: Vec3,
     weapon_type: &WeaponType,
-    damage: f32,
-) {
+    stats: &WeaponStats, // Damage already includes the player's multipliers
+    extra_effects: &[StatusEffect],
+    owner: Entity,
+) -> Entity {
     let (speed, lifetime, scale) = match weapon_type {
         WeaponType::Blaster => (800.0, 2.0, 0.15),
         WeaponType::Laser => (1200.0, 1.5, 0.12),
         WeaponType::Rocket => (600.0, 3.0, 0.2),
         WeaponType::AoePulse => (400.0, 1.0, 0.18),
+        WeaponType::PrismBeam => (1400.0, 1.5, 0.14),
+        WeaponType::ClusterMissiles => (650.0, 3.0, 0.22),
     };
     
     // Calculate rotation to face the direction of travel
@@ -361,6 +718,9 @@ fn spawn_projectile(
         Quat::IDENTITY
     };
     
+    let mut on_hit_effects: Vec<StatusEffect> = weapon_type.on_hit_effect().into_iter().collect();
+    on_hit_effects.extend_from_slice(extra_effects);
+    
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/stinger_missle.gltf#Scene0"),
@@ -371,13 +731,19 @@ fn spawn_projectile(
         },
         Projectile {
             velocity: direction * speed,
-            damage,
+            damage: stats.damage,
             lifetime: 0.0,
             max_lifetime: lifetime,
+            on_hit_effects,
+            owner: Some(owner),
+            pierce: stats.pierce,
+            splash_radius: stats.splash_radius,
+            hits: Vec::new(),
         },
+        InterpolatedTransform::default(),
         GameEntity,
         Name::new("Projectile"),
-    ));
+    )).id()
 }
 
 // Update projectiles
@@ -0,0 +1,344 @@
+//! Power-ups - timed buffs dropped as loot, plus the instant Nuke
+//!
+//! Each running buff is its own `ActiveBuff` entity under the player. The player's stat system
+//! folds them into `Player::buffs` every tick, and gameplay reads the combined modifiers from
+//! there.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::GameState;
+use crate::enemy::{Enemy, EnemyProjectile};
+use crate::player::Player;
+use crate::simulation::GameplaySet;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PowerUpConfig>()
+            .add_event::<PowerUpCollectedEvent>()
+            .add_systems(Startup, load_power_up_config)
+            .add_systems(FixedUpdate, tick_buffs_system
+                .after(crate::status::player_status_tick_system)
+                .in_set(GameplaySet::Status)
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, apply_power_up_system
+                .after(crate::loot::loot_pickup_system)
+                .before(crate::loot::loot_expiry_system)
+                .in_set(GameplaySet::Loot)
+                .run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerUpType {
+    Overdrive, // Faster fire rate
+    QuadDamage,
+    Invulnerability,
+    TimeSlow, // Enemies and their shots slow down
+    Magnet, // Pulls in every pickup on the field
+    Nuke, // Instant, hits everything near the player
+}
+
+impl PowerUpType {
+    pub const ALL: [PowerUpType; 6] = [
+        PowerUpType::Overdrive,
+        PowerUpType::QuadDamage,
+        PowerUpType::Invulnerability,
+        PowerUpType::TimeSlow,
+        PowerUpType::Magnet,
+        PowerUpType::Nuke,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUpType::Overdrive => "Overdrive",
+            PowerUpType::QuadDamage => "Quad Damage",
+            PowerUpType::Invulnerability => "Invulnerable",
+            PowerUpType::TimeSlow => "Time Slow",
+            PowerUpType::Magnet => "Magnet",
+            PowerUpType::Nuke => "Nuke",
+        }
+    }
+
+    // Short label for the HUD timer icon
+    pub fn icon_label(&self) -> &'static str {
+        match self {
+            PowerUpType::Overdrive => "OVR",
+            PowerUpType::QuadDamage => "x4",
+            PowerUpType::Invulnerability => "INV",
+            PowerUpType::TimeSlow => "SLO",
+            PowerUpType::Magnet => "MAG",
+            PowerUpType::Nuke => "NUK",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpType::Overdrive => Color::rgb(1.0, 0.85, 0.1),
+            PowerUpType::QuadDamage => Color::rgb(1.0, 0.1, 0.6),
+            PowerUpType::Invulnerability => Color::rgb(0.9, 0.95, 1.0),
+            PowerUpType::TimeSlow => Color::rgb(0.4, 0.3, 1.0),
+            PowerUpType::Magnet => Color::rgb(0.2, 1.0, 0.9),
+            PowerUpType::Nuke => Color::rgb(1.0, 0.4, 0.0),
+        }
+    }
+
+    /// What one stack of this power-up does, at the given strength
+    pub fn modifiers(&self, strength: f32) -> BuffModifiers {
+        let base = BuffModifiers::default();
+        match self {
+            PowerUpType::Overdrive => BuffModifiers { fire_rate_multiplier: strength, ..base },
+            PowerUpType::QuadDamage => BuffModifiers { damage_multiplier: strength, ..base },
+            PowerUpType::Invulnerability => BuffModifiers { invulnerable: true, ..base },
+            PowerUpType::TimeSlow => BuffModifiers { enemy_speed_multiplier: strength, ..base },
+            PowerUpType::Magnet => BuffModifiers { loot_vacuum: true, ..base },
+            PowerUpType::Nuke => base,
+        }
+    }
+}
+
+// What buffs change while they run. Multipliers combine by multiplying, flags by any-of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BuffModifiers {
+    pub fire_rate_multiplier: f32,
+    pub damage_multiplier: f32,
+    pub enemy_speed_multiplier: f32,
+    pub invulnerable: bool,
+    pub loot_vacuum: bool,
+}
+
+impl Default for BuffModifiers {
+    fn default() -> Self {
+        Self {
+            fire_rate_multiplier: 1.0,
+            damage_multiplier: 1.0,
+            enemy_speed_multiplier: 1.0,
+            invulnerable: false,
+            loot_vacuum: false,
+        }
+    }
+}
+
+impl BuffModifiers {
+    pub fn combine(&self, other: &BuffModifiers) -> BuffModifiers {
+        BuffModifiers {
+            fire_rate_multiplier: self.fire_rate_multiplier * other.fire_rate_multiplier,
+            damage_multiplier: self.damage_multiplier * other.damage_multiplier,
+            enemy_speed_multiplier: self.enemy_speed_multiplier * other.enemy_speed_multiplier,
+            invulnerable: self.invulnerable || other.invulnerable,
+            loot_vacuum: self.loot_vacuum || other.loot_vacuum,
+        }
+    }
+}
+
+// What picking up a buff that's already running does
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BuffStacking {
+    Refresh, // Restart the timer
+    Extend, // Add the new duration to what's left
+    Stack { max_stacks: u32 }, // Add a stack (each one applies the modifiers again) and restart the timer
+}
+
+/// A timed buff running on the entity it's parented to
+#[derive(Component, Clone, Debug)]
+pub struct ActiveBuff {
+    pub source: PowerUpType,
+    pub modifiers: BuffModifiers, // Per stack
+    pub stacking: BuffStacking,
+    pub stacks: u32,
+    pub duration: f32, // Length of the current timer, for the HUD
+    pub remaining: f32,
+}
+
+impl ActiveBuff {
+    pub fn new(source: PowerUpType, modifiers: BuffModifiers, stacking: BuffStacking, duration: f32) -> Self {
+        Self {
+            source,
+            modifiers,
+            stacking,
+            stacks: 1,
+            duration,
+            remaining: duration,
+        }
+    }
+
+    /// Picks up the same buff again, following its stacking rule
+    pub fn add(&mut self, duration: f32) {
+        match self.stacking {
+            BuffStacking::Refresh => {
+                self.remaining = self.remaining.max(duration);
+            }
+            BuffStacking::Extend => {
+                self.remaining += duration;
+            }
+            BuffStacking::Stack { max_stacks } => {
+                self.stacks = (self.stacks + 1).min(max_stacks.max(1));
+                self.remaining = self.remaining.max(duration);
+            }
+        }
+        self.duration = self.remaining;
+    }
+
+    pub fn total_modifiers(&self) -> BuffModifiers {
+        (0..self.stacks).fold(BuffModifiers::default(), |total, _| total.combine(&self.modifiers))
+    }
+}
+
+#[derive(Event)]
+pub struct PowerUpCollectedEvent {
+    pub power_up: PowerUpType,
+    pub duration_scale: f32, // The pickup's value, which scales the power-up's duration
+}
+
+// Power-up tuning, loaded from config/powerups.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct PowerUpConfig {
+    pub overdrive: PowerUpStats,
+    pub quad_damage: PowerUpStats,
+    pub invulnerability: PowerUpStats,
+    pub time_slow: PowerUpStats,
+    pub magnet: PowerUpStats,
+    pub nuke_radius: f32,
+    pub nuke_damage: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PowerUpStats {
+    pub duration: f32,
+    #[serde(default = "default_strength")]
+    pub strength: f32, // Multiplier for Overdrive, Quad Damage and Time Slow
+    pub stacking: BuffStacking,
+}
+
+fn default_strength() -> f32 {
+    1.0
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            overdrive: PowerUpStats { duration: 10.0, strength: 2.0, stacking: BuffStacking::Stack { max_stacks: 2 } },
+            quad_damage: PowerUpStats { duration: 10.0, strength: 4.0, stacking: BuffStacking::Refresh },
+            invulnerability: PowerUpStats { duration: 6.0, strength: 1.0, stacking: BuffStacking::Refresh },
+            time_slow: PowerUpStats { duration: 8.0, strength: 0.5, stacking: BuffStacking::Extend },
+            magnet: PowerUpStats { duration: 5.0, strength: 1.0, stacking: BuffStacking::Refresh },
+            nuke_radius: 600.0,
+            nuke_damage: 1000.0,
+        }
+    }
+}
+
+impl PowerUpConfig {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_str = std::fs::read_to_string("config/powerups.ron")?;
+        let config: PowerUpConfig = ron::de::from_str(&config_str)?;
+        Ok(config)
+    }
+
+    /// Timed stats for `power_up`, or None for the instant Nuke
+    pub fn stats(&self, power_up: PowerUpType) -> Option<&PowerUpStats> {
+        match power_up {
+            PowerUpType::Overdrive => Some(&self.overdrive),
+            PowerUpType::QuadDamage => Some(&self.quad_damage),
+            PowerUpType::Invulnerability => Some(&self.invulnerability),
+            PowerUpType::TimeSlow => Some(&self.time_slow),
+            PowerUpType::Magnet => Some(&self.magnet),
+            PowerUpType::Nuke => None,
+        }
+    }
+}
+
+// Systems
+fn load_power_up_config(mut config: ResMut<PowerUpConfig>) {
+    match PowerUpConfig::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded power-up config");
+            *config = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/powerups.ron ({}), using the default power-ups", err);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn apply_power_up_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<PowerUpConfig>,
+    mut collected_events: EventReader<PowerUpCollectedEvent>,
+    player_query: Query<(Entity, &Transform, Option<&Children>), With<Player>>,
+    mut buff_query: Query<&mut ActiveBuff>,
+    mut enemy_query: Query<(&Transform, &mut Enemy)>,
+    enemy_projectile_query: Query<(Entity, &Transform), With<EnemyProjectile>>,
+) {
+    let Ok((player_entity, player_transform, children)) = player_query.get_single() else {
+        return;
+    };
+
+    // Buffs picked up this tick aren't spawned yet, so repeats stack onto these instead
+    let mut new_buffs: Vec<ActiveBuff> = Vec::new();
+
+    for event in collected_events.read() {
+        let Some(stats) = config.stats(event.power_up) else {
+            // Nuke: everything near the player takes a huge hit and their shots vanish
+            info!("Nuke!");
+            let current_time = time.elapsed_seconds();
+            for (transform, mut enemy) in enemy_query.iter_mut() {
+                if transform.translation.distance(player_transform.translation) <= config.nuke_radius {
+                    enemy.last_attacker = Some(player_entity);
+                    enemy.take_damage(config.nuke_damage, current_time);
+                }
+            }
+            for (entity, transform) in enemy_projectile_query.iter() {
+                if transform.translation.distance(player_transform.translation) <= config.nuke_radius {
+                    commands.entity(entity).despawn();
+                }
+            }
+            continue;
+        };
+
+        let duration = stats.duration * event.duration_scale;
+        let running = children
+            .into_iter()
+            .flat_map(|children| children.iter())
+            .find(|&&child| buff_query.get(child).is_ok_and(|buff| buff.source == event.power_up))
+            .copied();
+
+        if let Some(mut buff) = running.and_then(|child| buff_query.get_mut(child).ok()) {
+            buff.add(duration);
+        } else if let Some(buff) = new_buffs.iter_mut().find(|buff| buff.source == event.power_up) {
+            buff.add(duration);
+        } else {
+            new_buffs.push(ActiveBuff::new(
+                event.power_up,
+                event.power_up.modifiers(stats.strength),
+                stats.stacking,
+                duration,
+            ));
+        }
+        info!("Power-up: {} ({:.1}s)", event.power_up.name(), duration);
+    }
+
+    for buff in new_buffs {
+        let name = Name::new(format!("Buff_{:?}", buff.source));
+        commands.entity(player_entity).with_children(|parent| {
+            parent.spawn((buff, name));
+        });
+    }
+}
+
+pub fn tick_buffs_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut buff_query: Query<(Entity, &mut ActiveBuff)>,
+) {
+    for (entity, mut buff) in buff_query.iter_mut() {
+        buff.remaining -= time.delta_seconds();
+        if buff.remaining <= 0.0 {
+            info!("{} wore off", buff.source.name());
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,839 @@
+//! Replay system - records every run's inputs and plays them back frame for frame
+//!
+//! A run is fully determined by its seed, its config and the inputs read on each frame, so a
+//! `.vsreplay` file only stores those: a header (seed, config hash, game mode) followed by one
+//! entry per frame with the frame's delta time, a bitmask of held inputs and, when it moved,
+//! the aim position. Frames where the level-up screen or the shop was clicked also list those
+//! clicks.
+//! Playback forces each recorded delta through `TimeUpdateStrategy`, writes the recorded inputs
+//! back into `Input<KeyCode>`, `Input<MouseButton>`, `Input<GamepadButton>` and `PlayerAim`,
+//! and sends the clicks again as upgrade and shop events.
+//!
+//! Watch one with `--replay <file>`. While watching, Space pauses, `-` and `=` halve and double
+//! the speed, R restarts, and Left / Right jump back / forward 10 seconds (jumping back restarts
+//! the run and fast-forwards to the target).
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::PresentMode;
+use bevy_rapier3d::prelude::RapierConfiguration;
+use crate::{GameMode, GameState};
+use crate::config::{EnemyConfig, GameConfig, PlayerConfig, WaveConfig};
+use crate::loot::LootTables;
+use crate::player::{capture_player_aim_system, PlayerAim};
+use crate::powerup::PowerUpConfig;
+use crate::rng::{reseed_for_new_run, GameRng, SeedSettings};
+use crate::shop::{ShopConfig, ShopPurchaseEvent};
+use crate::survival::SurvivalTimeline;
+use crate::upgrade::{UpgradeBanishEvent, UpgradeCatalogue, UpgradeChosenEvent, UpgradeHighlightEvent, UpgradeRerollEvent};
+use crate::wave::WaveScript;
+
+pub const REPLAY_EXTENSION: &str = "vsreplay";
+const REPLAY_DIR: &str = "replays";
+const MAGIC: &[u8; 4] = b"VSRP";
+const VERSION: u16 = 2; // 2 added gamepad buttons and clicks, version 1 files still load
+
+// Every input gameplay reads, one bit each. Append only - the bit order is the file format.
+const TRACKED_KEYS: [KeyCode; 23] = [
+    KeyCode::W,
+    KeyCode::A,
+    KeyCode::S,
+    KeyCode::D,
+    KeyCode::Up,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Right,
+    KeyCode::Space,
+    KeyCode::Escape,
+    KeyCode::Return,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::ShiftLeft,
+    KeyCode::R,
+    KeyCode::B,
+];
+// Recorded from any gamepad, played back on the first one. Append only, after the keys.
+const TRACKED_GAMEPAD_BUTTONS: [GamepadButtonType; 5] = [
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+    GamepadButtonType::South,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+];
+const GAMEPAD_FIRST_BIT: usize = TRACKED_KEYS.len();
+const FIRE_BUTTON_BIT: u32 = 1 << 29;
+const CLICKS_BIT: u32 = 1 << 30;
+const AIM_CHANGED_BIT: u32 = 1 << 31;
+
+const SEEK_STEP: f32 = 10.0;
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 8.0;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ReplayRecorder>()
+            .add_systems(OnExit(GameState::MainMenu), (
+                reset_run_clock,
+                start_recording_system.after(reseed_for_new_run),
+            ))
+            .add_systems(OnEnter(GameState::GameOver), save_recording_system)
+            .add_systems(Last, (
+                record_frame_system,
+                save_recording_on_exit_system.after(record_frame_system),
+            ));
+    }
+}
+
+// Plays back the replay given with `--replay <file>`, if there is one
+pub struct ReplayViewerPlugin;
+
+impl Plugin for ReplayViewerPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(path) = replay_path_from_args(std::env::args().skip(1)) else {
+            return;
+        };
+
+        match Replay::load(&path) {
+            Ok(replay) => {
+                info!("Playing back {} ({} frames, seed {})", path.display(), replay.frames.len(), replay.seed);
+                // Same seed and mode as the recorded run; nothing gets recorded while watching
+                app
+                    .insert_resource(SeedSettings { fixed_seed: Some(replay.seed) })
+                    .insert_resource(replay.game_mode)
+                    .insert_resource(ReplayRecorder { enabled: false, ..default() })
+                    .insert_resource(ReplayPlayback::new(replay))
+                    .add_systems(Startup, setup_replay_viewer)
+                    .add_systems(OnExit(GameState::MainMenu), verify_replay_config_system)
+                    .add_systems(PreUpdate, playback_input_system.after(capture_player_aim_system))
+                    .add_systems(Update, update_replay_overlay)
+                    .add_systems(Last, playback_advance_system);
+            }
+            Err(err) => {
+                error!("Could not load replay {} ({})", path.display(), err);
+            }
+        }
+    }
+}
+
+fn replay_path_from_args(mut args: impl Iterator<Item = String>) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
+
+// A click on the level-up screen or the shop, as the event it sent
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClickInput {
+    Highlight(usize),
+    Choose(usize),
+    Banish(usize),
+    Reroll,
+    ShopPurchase(usize),
+}
+
+impl ClickInput {
+    fn to_bytes(self) -> [u8; 2] {
+        match self {
+            ClickInput::Highlight(index) => [0, index as u8],
+            ClickInput::Choose(index) => [1, index as u8],
+            ClickInput::Banish(index) => [2, index as u8],
+            ClickInput::Reroll => [3, 0],
+            ClickInput::ShopPurchase(slot) => [4, slot as u8],
+        }
+    }
+
+    fn from_bytes(bytes: [u8; 2]) -> Result<Self, Box<dyn std::error::Error>> {
+        let index = bytes[1] as usize;
+        match bytes[0] {
+            0 => Ok(ClickInput::Highlight(index)),
+            1 => Ok(ClickInput::Choose(index)),
+            2 => Ok(ClickInput::Banish(index)),
+            3 => Ok(ClickInput::Reroll),
+            4 => Ok(ClickInput::ShopPurchase(index)),
+            other => Err(format!("unknown click {}", other).into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub delta: Duration,
+    pub buttons: u32,
+    pub aim: Option<Vec2>,
+    pub clicks: Vec<ClickInput>, // Clicks on the level-up screen and the shop this frame
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub config_hash: u64,
+    pub game_mode: GameMode,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, config_hash: u64, game_mode: GameMode) -> Self {
+        Self {
+            seed,
+            config_hash,
+            game_mode,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.delta).sum()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 + self.frames.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.config_hash.to_le_bytes());
+        bytes.push(match self.game_mode {
+            GameMode::Waves => 0,
+            GameMode::Survival => 1,
+        });
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        // Aim is only written on the frames where it changes
+        let mut last_aim = None;
+        for frame in &self.frames {
+            let aim_changed = frame.aim != last_aim;
+            let mut buttons = frame.buttons;
+            if aim_changed {
+                buttons |= AIM_CHANGED_BIT;
+            }
+            if !frame.clicks.is_empty() {
+                buttons |= CLICKS_BIT;
+            }
+
+            bytes.extend_from_slice(&(frame.delta.as_nanos() as u32).to_le_bytes());
+            bytes.extend_from_slice(&buttons.to_le_bytes());
+            if aim_changed {
+                let aim = frame.aim.unwrap_or(Vec2::NAN);
+                bytes.extend_from_slice(&aim.x.to_le_bytes());
+                bytes.extend_from_slice(&aim.y.to_le_bytes());
+                last_aim = frame.aim;
+            }
+            if !frame.clicks.is_empty() {
+                bytes.push(frame.clicks.len() as u8);
+                for input in &frame.clicks {
+                    bytes.extend_from_slice(&input.to_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = ByteReader { bytes };
+
+        if reader.take(4)? != MAGIC {
+            return Err("not a replay file".into());
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if !(1..=VERSION).contains(&version) {
+            return Err(format!("unsupported replay version {}", version).into());
+        }
+
+        let seed = u64::from_le_bytes(reader.array()?);
+        let config_hash = u64::from_le_bytes(reader.array()?);
+        let game_mode = match reader.take(1)?[0] {
+            0 => GameMode::Waves,
+            1 => GameMode::Survival,
+            other => return Err(format!("unknown game mode {}", other).into()),
+        };
+        let frame_count = u32::from_le_bytes(reader.array()?);
+
+        let mut replay = Replay::new(seed, config_hash, game_mode);
+        let mut aim = None;
+        for _ in 0..frame_count {
+            let delta = Duration::from_nanos(u32::from_le_bytes(reader.array()?) as u64);
+            let buttons = u32::from_le_bytes(reader.array()?);
+            if buttons & AIM_CHANGED_BIT != 0 {
+                let x = f32::from_le_bytes(reader.array()?);
+                let y = f32::from_le_bytes(reader.array()?);
+                aim = if x.is_nan() { None } else { Some(Vec2::new(x, y)) };
+            }
+            let mut clicks = Vec::new();
+            if buttons & CLICKS_BIT != 0 {
+                let count = reader.take(1)?[0];
+                for _ in 0..count {
+                    clicks.push(ClickInput::from_bytes(reader.array()?)?);
+                }
+            }
+            replay.frames.push(ReplayFrame {
+                delta,
+                buttons: buttons & !(AIM_CHANGED_BIT | CLICKS_BIT),
+                aim,
+                clicks,
+            });
+        }
+
+        Ok(replay)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Writes the replay into `directory` and returns where it went
+    pub fn save(&self, directory: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(directory)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = directory.join(format!("run_{}_{}.{}", timestamp, self.seed, REPLAY_EXTENSION));
+        std::fs::write(&path, self.to_bytes())?;
+        Ok(path)
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        if self.bytes.len() < count {
+            return Err("replay file is truncated".into());
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Box<dyn std::error::Error>> {
+        Ok(self.take(N)?.try_into()?)
+    }
+}
+
+pub fn capture_buttons(keys: &Input<KeyCode>, mouse: &Input<MouseButton>, gamepad: &Input<GamepadButton>) -> u32 {
+    let mut buttons = 0;
+    for (bit, key) in TRACKED_KEYS.iter().enumerate() {
+        if keys.pressed(*key) {
+            buttons |= 1 << bit;
+        }
+    }
+    for (offset, button_type) in TRACKED_GAMEPAD_BUTTONS.iter().enumerate() {
+        if gamepad.get_pressed().any(|button| button.button_type == *button_type) {
+            buttons |= 1 << (GAMEPAD_FIRST_BIT + offset);
+        }
+    }
+    if mouse.pressed(MouseButton::Left) {
+        buttons |= FIRE_BUTTON_BIT;
+    }
+    buttons
+}
+
+/// Replaces the tracked inputs with `buttons`, so `just_pressed` fires exactly where it did when recorded
+pub fn apply_buttons(
+    buttons: u32,
+    keys: &mut Input<KeyCode>,
+    mouse: &mut Input<MouseButton>,
+    gamepad: &mut Input<GamepadButton>,
+) {
+    keys.clear();
+    mouse.clear();
+    gamepad.clear();
+
+    for (bit, key) in TRACKED_KEYS.iter().enumerate() {
+        if buttons & (1 << bit) != 0 {
+            keys.press(*key);
+        } else {
+            keys.release(*key);
+        }
+    }
+    for (offset, button_type) in TRACKED_GAMEPAD_BUTTONS.iter().enumerate() {
+        let button = GamepadButton::new(Gamepad::new(0), *button_type);
+        if buttons & (1 << (GAMEPAD_FIRST_BIT + offset)) != 0 {
+            gamepad.press(button);
+        } else {
+            gamepad.release(button);
+        }
+    }
+    if buttons & FIRE_BUTTON_BIT != 0 {
+        mouse.press(MouseButton::Left);
+    } else {
+        mouse.release(MouseButton::Left);
+    }
+}
+
+// Everything a run's outcome depends on besides the seed and inputs
+#[derive(SystemParam)]
+pub struct RunConfig<'w> {
+    game: Res<'w, GameConfig>,
+    player: Res<'w, PlayerConfig>,
+    enemy: Res<'w, EnemyConfig>,
+    wave: Res<'w, WaveConfig>,
+    loot_tables: Res<'w, LootTables>,
+    power_ups: Res<'w, PowerUpConfig>,
+    shop: Res<'w, ShopConfig>,
+    upgrades: Res<'w, UpgradeCatalogue>,
+    wave_script: Res<'w, WaveScript>,
+    survival_timeline: Res<'w, SurvivalTimeline>,
+}
+
+impl RunConfig<'_> {
+    /// FNV-1a over the serialized config, stable across builds and platforms
+    pub fn hash(&self) -> u64 {
+        let serialized = [
+            ron::to_string(&*self.game),
+            ron::to_string(&*self.player),
+            ron::to_string(&*self.enemy),
+            ron::to_string(&*self.wave),
+            ron::to_string(&*self.loot_tables),
+            ron::to_string(&*self.power_ups),
+            ron::to_string(&*self.shop),
+            ron::to_string(&*self.upgrades),
+            ron::to_string(&*self.wave_script),
+            ron::to_string(&*self.survival_timeline),
+        ];
+
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for part in serialized {
+            for byte in part.unwrap_or_default().bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash
+    }
+}
+
+// The events the level-up screen and the shop send when clicked, read back for recording
+#[derive(SystemParam)]
+pub struct ClickInputReader<'w, 's> {
+    highlight: EventReader<'w, 's, UpgradeHighlightEvent>,
+    chosen: EventReader<'w, 's, UpgradeChosenEvent>,
+    banish: EventReader<'w, 's, UpgradeBanishEvent>,
+    reroll: EventReader<'w, 's, UpgradeRerollEvent>,
+    shop_purchase: EventReader<'w, 's, ShopPurchaseEvent>,
+}
+
+impl ClickInputReader<'_, '_> {
+    pub fn read(&mut self) -> Vec<ClickInput> {
+        let mut inputs: Vec<ClickInput> = self.highlight.read().map(|event| ClickInput::Highlight(event.index)).collect();
+        inputs.extend(self.chosen.read().map(|event| ClickInput::Choose(event.index)));
+        inputs.extend(self.banish.read().map(|event| ClickInput::Banish(event.index)));
+        inputs.extend(self.reroll.read().map(|_| ClickInput::Reroll));
+        inputs.extend(self.shop_purchase.read().map(|event| ClickInput::ShopPurchase(event.slot)));
+        inputs
+    }
+}
+
+// Sends recorded clicks as the events the screens sent at the time
+#[derive(SystemParam)]
+pub struct ClickInputWriter<'w> {
+    highlight: EventWriter<'w, UpgradeHighlightEvent>,
+    chosen: EventWriter<'w, UpgradeChosenEvent>,
+    banish: EventWriter<'w, UpgradeBanishEvent>,
+    reroll: EventWriter<'w, UpgradeRerollEvent>,
+    shop_purchase: EventWriter<'w, ShopPurchaseEvent>,
+}
+
+impl ClickInputWriter<'_> {
+    pub fn send(&mut self, input: ClickInput) {
+        match input {
+            ClickInput::Highlight(index) => self.highlight.send(UpgradeHighlightEvent { index }),
+            ClickInput::Choose(index) => self.chosen.send(UpgradeChosenEvent { index }),
+            ClickInput::Banish(index) => self.banish.send(UpgradeBanishEvent { index }),
+            ClickInput::Reroll => self.reroll.send(UpgradeRerollEvent),
+            ClickInput::ShopPurchase(slot) => self.shop_purchase.send(ShopPurchaseEvent { slot }),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub enabled: bool,
+    pub replay: Option<Replay>, // The run being recorded right now
+    pub directory: PathBuf, // Where finished runs are saved
+    pub last_saved: Option<PathBuf>,
+}
+
+impl Default for ReplayRecorder {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            replay: None,
+            directory: PathBuf::from(REPLAY_DIR),
+            last_saved: None,
+        }
+    }
+}
+
+impl ReplayRecorder {
+    pub fn save(&mut self) {
+        let Some(replay) = self.replay.take() else {
+            return;
+        };
+
+        match replay.save(&self.directory) {
+            Ok(path) => {
+                info!("Saved replay to {} ({} frames)", path.display(), replay.frames.len());
+                self.last_saved = Some(path);
+            }
+            Err(err) => warn!("Could not save replay ({})", err),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackStatus {
+    Starting, // Waiting in the main menu to kick off the run
+    Running,
+    Finished,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub status: PlaybackStatus,
+    pub next_frame: usize,
+    pub paused: bool,
+    pub speed: f32,
+    pub seek_target: Option<usize>,
+    frame_start_times: Vec<f32>,
+    playing_frame: bool, // This update consumes `next_frame`
+    holding_pause: bool, // We put the game in Paused to hold it between frames
+    restart_requested: bool,
+    budget: f32,
+    last_wall_time: Option<Instant>,
+    last_buttons: u32,
+    last_aim: Option<Vec2>,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        let frame_start_times = replay.frames
+            .iter()
+            .scan(0.0, |time, frame| {
+                let start = *time;
+                *time += frame.delta.as_secs_f32();
+                Some(start)
+            })
+            .collect();
+
+        Self {
+            replay,
+            status: PlaybackStatus::Starting,
+            next_frame: 0,
+            paused: false,
+            speed: 1.0,
+            seek_target: None,
+            frame_start_times,
+            playing_frame: false,
+            holding_pause: false,
+            restart_requested: false,
+            budget: 0.0,
+            last_wall_time: None,
+            last_buttons: 0,
+            last_aim: None,
+        }
+    }
+
+    pub fn current_time(&self) -> f32 {
+        self.frame_start_times.get(self.next_frame).copied()
+            .unwrap_or_else(|| self.replay.duration().as_secs_f32())
+    }
+
+    /// Jumps to `time` seconds into the run. Going backwards restarts the run and fast-forwards.
+    pub fn seek(&mut self, time: f32) {
+        let target = self.frame_start_times.partition_point(|start| *start < time);
+        if target < self.next_frame {
+            self.restart_requested = true;
+        }
+        self.seek_target = Some(target.min(self.replay.frames.len()));
+    }
+
+    fn hold(&mut self, time_strategy: &mut TimeUpdateStrategy) {
+        *time_strategy = TimeUpdateStrategy::ManualDuration(Duration::ZERO);
+        self.playing_frame = false;
+    }
+}
+
+#[derive(Component)]
+pub struct ReplayOverlay;
+
+// Systems
+fn reset_run_clock(
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut time: ResMut<Time>,
+) {
+    // Every run's clock starts from zero, so elapsed-time cooldowns line up with the replay
+    let mut run_time = Time::<Virtual>::default();
+    run_time.set_max_delta(virtual_time.max_delta());
+    run_time.advance_by(time.delta());
+    *virtual_time = run_time;
+    *time = virtual_time.as_generic();
+    // Drop leftover time from the menu too, so ticks fall on the same frames as the recording
+    *fixed_time = Time::<Fixed>::from_duration(fixed_time.timestep());
+}
+
+fn start_recording_system(
+    game_mode: Res<GameMode>,
+    game_rng: Res<GameRng>,
+    run_config: RunConfig,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    if recorder.enabled {
+        recorder.replay = Some(Replay::new(game_rng.seed(), run_config.hash(), *game_mode));
+    }
+}
+
+fn record_frame_system(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad: Res<Input<GamepadButton>>,
+    aim: Res<PlayerAim>,
+    mut click_inputs: ClickInputReader,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    // Read every frame, so clicks from before the recording starts don't end up in it
+    let clicks = click_inputs.read();
+    if let Some(replay) = recorder.replay.as_mut() {
+        replay.frames.push(ReplayFrame {
+            delta: time.delta(),
+            buttons: capture_buttons(&keys, &mouse, &gamepad),
+            aim: aim.cursor_offset,
+            clicks,
+        });
+    }
+}
+
+fn save_recording_system(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.save();
+}
+
+fn save_recording_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    if exit_events.read().next().is_some() {
+        recorder.save();
+    }
+}
+
+fn verify_replay_config_system(
+    playback: Res<ReplayPlayback>,
+    run_config: RunConfig,
+) {
+    if run_config.hash() != playback.replay.config_hash {
+        warn!("Replay was recorded with a different config - playback will likely diverge");
+    }
+}
+
+fn setup_replay_viewer(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut windows: Query<&mut Window>,
+) {
+    // Uncapped frame rate so fast-forward isn't limited by vsync
+    for mut window in windows.iter_mut() {
+        window.present_mode = PresentMode::AutoNoVsync;
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                font_size: 18.0,
+                color: Color::YELLOW,
+            },
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            ..default()
+        }),
+        ReplayOverlay,
+        Name::new("ReplayOverlay"),
+    ));
+}
+
+fn playback_input_system(
+    mut playback: ResMut<ReplayPlayback>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut gamepad: ResMut<Input<GamepadButton>>,
+    mut aim: ResMut<PlayerAim>,
+    mut click_inputs: ClickInputWriter,
+) {
+    // Viewer controls come from the real keyboard, read before it gets overwritten
+    if keys.just_pressed(KeyCode::Space) {
+        playback.paused = !playback.paused;
+    }
+    if keys.just_pressed(KeyCode::Minus) {
+        playback.speed = (playback.speed * 0.5).max(MIN_SPEED);
+    }
+    if keys.just_pressed(KeyCode::Equals) {
+        playback.speed = (playback.speed * 2.0).min(MAX_SPEED);
+    }
+    if keys.just_pressed(KeyCode::R) {
+        playback.seek(0.0);
+    }
+    if keys.just_pressed(KeyCode::Left) {
+        let time = playback.current_time() - SEEK_STEP;
+        playback.seek(time.max(0.0));
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        let time = playback.current_time() + SEEK_STEP;
+        playback.seek(time);
+    }
+
+    // Held frames repeat the last inputs without re-triggering just_pressed
+    let (buttons, frame_aim) = match playback.replay.frames.get(playback.next_frame) {
+        Some(frame) if playback.playing_frame => {
+            // Clicks only happen once, on the frame they were recorded
+            for input in &frame.clicks {
+                click_inputs.send(*input);
+            }
+            (frame.buttons, frame.aim)
+        }
+        _ => (playback.last_buttons, playback.last_aim),
+    };
+    apply_buttons(buttons, &mut keys, &mut mouse, &mut gamepad);
+    aim.cursor_offset = frame_aim;
+    playback.last_buttons = buttons;
+    playback.last_aim = frame_aim;
+}
+
+fn playback_advance_system(
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    if playback.playing_frame {
+        playback.next_frame += 1;
+        playback.playing_frame = false;
+    }
+
+    // Back through the main menu, which clears out the run before the replay starts it again
+    if playback.restart_requested {
+        playback.restart_requested = false;
+        playback.holding_pause = false;
+        playback.status = PlaybackStatus::Starting;
+        playback.next_frame = 0;
+        playback.last_buttons = 0;
+        playback.last_aim = None;
+        playback.hold(&mut time_strategy);
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+
+    let wall_time = Instant::now();
+    let wall_delta = playback.last_wall_time.map_or(0.0, |last| (wall_time - last).as_secs_f32());
+    playback.last_wall_time = Some(wall_time);
+
+    let Some(frame) = playback.replay.frames.get(playback.next_frame).cloned() else {
+        if playback.status != PlaybackStatus::Finished {
+            info!("Replay finished");
+            playback.status = PlaybackStatus::Finished;
+        }
+        playback.hold(&mut time_strategy);
+        return;
+    };
+
+    if playback.status == PlaybackStatus::Starting {
+        // The run starts on the next update, with the first recorded frame
+        if *state.get() == GameState::MainMenu {
+            playback.status = PlaybackStatus::Running;
+            playback.playing_frame = true;
+            playback.budget = 0.0;
+            *time_strategy = TimeUpdateStrategy::ManualDuration(frame.delta);
+            rapier_config.physics_pipeline_active = true;
+            next_state.set(GameState::Playing);
+        } else {
+            playback.hold(&mut time_strategy);
+        }
+        return;
+    }
+
+    if playback.seek_target.is_some_and(|target| playback.next_frame >= target) {
+        playback.seek_target = None;
+        playback.budget = 0.0;
+    }
+
+    let play = if playback.seek_target.is_some() {
+        true // Fast-forward as quickly as frames can render
+    } else if playback.paused {
+        false
+    } else {
+        let frame_time = frame.delta.as_secs_f32();
+        playback.budget = (playback.budget + wall_delta * playback.speed).min(frame_time.max(0.25));
+        if playback.budget >= frame_time {
+            playback.budget -= frame_time;
+            true
+        } else {
+            false
+        }
+    };
+
+    if play {
+        if playback.holding_pause {
+            next_state.set(GameState::Playing);
+            playback.holding_pause = false;
+        }
+        playback.playing_frame = true;
+        *time_strategy = TimeUpdateStrategy::ManualDuration(frame.delta);
+    } else {
+        // Park the game in Paused so nothing simulates on frames that don't consume input
+        if !playback.holding_pause && *state.get() == GameState::Playing && next_state.0.is_none() {
+            next_state.set(GameState::Paused);
+            playback.holding_pause = true;
+        }
+        playback.hold(&mut time_strategy);
+    }
+    rapier_config.physics_pipeline_active = play;
+}
+
+fn update_replay_overlay(
+    playback: Res<ReplayPlayback>,
+    mut overlay_query: Query<&mut Text, With<ReplayOverlay>>,
+) {
+    let format_time = |seconds: f32| {
+        let seconds = seconds as u32;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    };
+
+    if let Ok(mut text) = overlay_query.get_single_mut() {
+        let status = match (playback.status, playback.paused, playback.seek_target.is_some()) {
+            (PlaybackStatus::Finished, _, _) => " [END]",
+            (_, _, true) => " [SEEKING]",
+            (_, true, _) => " [PAUSED]",
+            _ => "",
+        };
+        text.sections[0].value = format!(
+            "REPLAY {:.2}x  {} / {}{}",
+            playback.speed,
+            format_time(playback.current_time()),
+            format_time(playback.replay.duration().as_secs_f32()),
+            status,
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Seeded randomness - one seed per run, split into independent streams
+//!
+//! Every gameplay roll goes through `GameRng` so a run can be replayed from its seed.
+//! Each stream is its own ChaCha sequence, so drawing more numbers from one (e.g. extra
+//! particles) never shifts the rolls in another.
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use crate::GameState;
+
+pub type RngStream = ChaCha8Rng;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = SeedSettings::from_args(std::env::args().skip(1));
+        let seed = settings.next_seed();
+
+        app
+            .insert_resource(GameRng::new(seed))
+            .insert_resource(settings)
+            .add_systems(OnExit(GameState::MainMenu), reseed_for_new_run);
+    }
+}
+
+// Where run seeds come from, set on the command line:
+//   --seed <n>   play every run with seed n
+//   --daily      play today's seed (same for everyone on the same UTC day)
+#[derive(Resource, Default, Debug)]
+pub struct SeedSettings {
+    pub fixed_seed: Option<u64>,
+}
+
+impl SeedSettings {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut settings = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => match args.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seed)) => settings.fixed_seed = Some(seed),
+                    _ => warn!("--seed expects a number, ignoring"),
+                },
+                "--daily" => settings.fixed_seed = Some(daily_seed()),
+                _ => {}
+            }
+        }
+
+        settings
+    }
+
+    pub fn next_seed(&self) -> u64 {
+        self.fixed_seed.unwrap_or_else(rand::random)
+    }
+}
+
+/// Seed shared by everyone playing on the same UTC day
+pub fn daily_seed() -> u64 {
+    let days = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400);
+    // Spread consecutive days apart so neighbouring dailies don't look alike
+    days.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    spawns: RngStream,
+    loot: RngStream,
+    upgrades: RngStream,
+    combat: RngStream,
+    cosmetics: RngStream,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        let stream = |id: u64| {
+            let mut rng = RngStream::seed_from_u64(seed);
+            rng.set_stream(id);
+            rng
+        };
+
+        Self {
+            seed,
+            spawns: stream(0),
+            loot: stream(1),
+            upgrades: stream(2),
+            combat: stream(3),
+            cosmetics: stream(4),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Enemy composition, spawn positions, asteroid layout
+    pub fn spawns(&mut self) -> &mut RngStream {
+        &mut self.spawns
+    }
+
+    pub fn loot(&mut self) -> &mut RngStream {
+        &mut self.loot
+    }
+
+    pub fn upgrades(&mut self) -> &mut RngStream {
+        &mut self.upgrades
+    }
+
+    // In-fight rolls such as reflects and teleports
+    pub fn combat(&mut self) -> &mut RngStream {
+        &mut self.combat
+    }
+
+    // Particles and other effects that never feed back into gameplay
+    pub fn cosmetics(&mut self) -> &mut RngStream {
+        &mut self.cosmetics
+    }
+}
+
+// Systems
+pub fn reseed_for_new_run(
+    settings: Res<SeedSettings>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    *game_rng = GameRng::new(settings.next_seed());
+    info!("Run seed: {}", game_rng.seed());
+}
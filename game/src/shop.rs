@@ -0,0 +1,317 @@
+//! Shop - spend credits on upgrades, repairs and buffs during wave breaks
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{GameMode, GameState};
+use crate::player::{Player, WeaponType};
+use crate::powerup::{PowerUpCollectedEvent, PowerUpType};
+use crate::rng::GameRng;
+use crate::simulation::GameplaySet;
+use crate::upgrade::{Upgrade, UpgradeCatalogue, UpgradeManager, UpgradeType};
+use crate::wave::{WaveCompleteEvent, WaveManager};
+
+// Slots are always stocked in this order, bought with keys 4 to 9
+pub const SHOP_SLOTS: usize = 6;
+pub const SHOP_KEYS: [KeyCode; SHOP_SLOTS] = [KeyCode::Key4, KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9];
+const UPGRADE_SLOT: usize = 0;
+const WEAPON_SLOT: usize = 1;
+const BUFF_SLOT: usize = 2;
+const REROLL_SLOT: usize = 5;
+
+// Timed power-ups the shop sells. The Nuke only drops.
+const SHOP_BUFFS: [PowerUpType; 5] = [
+    PowerUpType::Overdrive,
+    PowerUpType::QuadDamage,
+    PowerUpType::Invulnerability,
+    PowerUpType::TimeSlow,
+    PowerUpType::Magnet,
+];
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Shop>()
+            .init_resource::<ShopConfig>()
+            .add_event::<ShopPurchaseEvent>()
+            .add_systems(Startup, load_shop_config)
+            // Purchases are key presses and clicks, so the shop runs per frame like the break pick
+            .add_systems(Update, (
+                open_shop_system,
+                close_shop_system,
+                shop_purchase_system,
+            ).chain()
+             .run_if(in_state(GameState::Playing))
+             .run_if(resource_equals(GameMode::Waves)))
+            // Bought buffs start on the next tick; a frame can pass without one
+            .add_systems(FixedUpdate, deliver_bought_buffs_system
+                .before(crate::powerup::apply_power_up_system)
+                .in_set(GameplaySet::Loot)
+                .run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Shop {
+    pub open: bool,
+    pub wave: u32, // Wave the prices were set for
+    pub rerolls: u32, // This break, each one costs more
+    pub offers: Vec<ShopOffer>,
+    pub bought_buffs: Vec<PowerUpType>, // Paid for, waiting for the next tick to start
+}
+
+#[derive(Clone, Debug)]
+pub struct ShopOffer {
+    pub item: ShopItem,
+    pub price: u32,
+    pub sold: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum ShopItem {
+    Upgrade(Upgrade), // Weapon upgrades are `WeaponUnlock` upgrades for an owned weapon
+    Buff(PowerUpType),
+    Repair(f32),
+    ShieldRefill,
+    Reroll,
+}
+
+impl ShopItem {
+    pub fn name(&self) -> String {
+        match self {
+            ShopItem::Upgrade(upgrade) => upgrade.name.clone(),
+            ShopItem::Buff(power_up) => power_up.name().to_string(),
+            ShopItem::Repair(_) => "Hull Repair".to_string(),
+            ShopItem::ShieldRefill => "Shield Refill".to_string(),
+            ShopItem::Reroll => "Reroll".to_string(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            ShopItem::Upgrade(upgrade) => upgrade.description.clone(),
+            ShopItem::Buff(_) => "Starts the power-up now".to_string(),
+            ShopItem::Repair(amount) => format!("Restore {} health", amount),
+            ShopItem::ShieldRefill => "Fully recharge shields".to_string(),
+            ShopItem::Reroll => "New upgrade, weapon and buff offers".to_string(),
+        }
+    }
+}
+
+// Buy the offer in `slot`, sent by clicking the shop panel
+#[derive(Event)]
+pub struct ShopPurchaseEvent {
+    pub slot: usize,
+}
+
+// Shop prices, loaded from config/shop.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct ShopConfig {
+    pub price_scaling_per_wave: f32, // Each wave past the first adds this much to every price
+    pub upgrade_price: u32,
+    pub weapon_upgrade_price: u32,
+    pub buff_price: u32,
+    pub repair_price: u32,
+    pub repair_amount: f32,
+    pub shield_refill_price: u32,
+    pub reroll_price: u32,
+    pub reroll_price_increase: u32, // Per reroll already bought this break
+}
+
+impl Default for ShopConfig {
+    fn default() -> Self {
+        Self {
+            price_scaling_per_wave: 0.15,
+            upgrade_price: 40,
+            weapon_upgrade_price: 60,
+            buff_price: 30,
+            repair_price: 20,
+            repair_amount: 50.0,
+            shield_refill_price: 15,
+            reroll_price: 10,
+            reroll_price_increase: 5,
+        }
+    }
+}
+
+impl ShopConfig {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_str = std::fs::read_to_string("config/shop.ron")?;
+        let config: ShopConfig = ron::de::from_str(&config_str)?;
+        Ok(config)
+    }
+
+    pub fn price(&self, base: u32, wave: u32) -> u32 {
+        let scaling = 1.0 + wave.saturating_sub(1) as f32 * self.price_scaling_per_wave;
+        (base as f32 * scaling).round() as u32
+    }
+}
+
+impl Shop {
+    /// Fills every slot for a new break
+    pub fn restock(&mut self, config: &ShopConfig, upgrade_manager: &UpgradeManager, catalogue: &UpgradeCatalogue, player: &Player, wave: u32, rng: &mut impl Rng) {
+        self.wave = wave;
+        self.rerolls = 0;
+        let offer = |item, price| ShopOffer { item, price, sold: false };
+        self.offers = vec![
+            // The upgrade, weapon and buff slots are filled in by the reroll below
+            offer(ShopItem::Reroll, 0),
+            offer(ShopItem::Reroll, 0),
+            offer(ShopItem::Reroll, 0),
+            offer(ShopItem::Repair(config.repair_amount), config.price(config.repair_price, wave)),
+            offer(ShopItem::ShieldRefill, config.price(config.shield_refill_price, wave)),
+            offer(ShopItem::Reroll, 0),
+        ];
+        self.reroll(config, upgrade_manager, catalogue, player, rng);
+    }
+
+    /// New upgrade, weapon and buff offers, and a pricier next reroll
+    pub fn reroll(&mut self, config: &ShopConfig, upgrade_manager: &UpgradeManager, catalogue: &UpgradeCatalogue, player: &Player, rng: &mut impl Rng) {
+        let wave = self.wave;
+        let tier = (player.level / 5).max(1);
+
+        // Upgrades come from the same pool as level-up picks
+        let upgrade = upgrade_manager
+            .generate_random_upgrades(catalogue, player, 1, &[], rng)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Upgrade::new(UpgradeType::HealthIncrease(25.0), tier));
+        self.offers[UPGRADE_SLOT] = ShopOffer {
+            item: ShopItem::Upgrade(upgrade),
+            price: config.price(config.upgrade_price, wave),
+            sold: false,
+        };
+
+        // Level up a weapon the player already has, or unlock one if everything owned is maxed
+        let owned: Vec<WeaponType> = player.weapon_crate_options()
+            .into_iter()
+            .filter(|weapon_type| player.arsenal.iter().any(|weapon| weapon.weapon_type == *weapon_type))
+            .collect();
+        let options = if owned.is_empty() { player.weapon_crate_options() } else { owned };
+        self.offers[WEAPON_SLOT] = match options.len() {
+            // Every weapon is maxed out, so sell another repair instead
+            0 => ShopOffer { item: ShopItem::Repair(config.repair_amount), price: config.price(config.repair_price, wave), sold: false },
+            len => ShopOffer {
+                item: ShopItem::Upgrade(Upgrade::new(UpgradeType::WeaponUnlock(options[rng.gen_range(0..len)].clone()), tier)),
+                price: config.price(config.weapon_upgrade_price, wave),
+                sold: false,
+            },
+        };
+
+        self.offers[BUFF_SLOT] = ShopOffer {
+            item: ShopItem::Buff(SHOP_BUFFS[rng.gen_range(0..SHOP_BUFFS.len())]),
+            price: config.price(config.buff_price, wave),
+            sold: false,
+        };
+
+        self.offers[REROLL_SLOT].price = config.price(config.reroll_price + self.rerolls * config.reroll_price_increase, wave);
+    }
+}
+
+// Systems
+fn load_shop_config(mut config: ResMut<ShopConfig>) {
+    match ShopConfig::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded shop config");
+            *config = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/shop.ron ({}), using the default prices", err);
+        }
+    }
+}
+
+fn open_shop_system(
+    config: Res<ShopConfig>,
+    catalogue: Res<UpgradeCatalogue>,
+    mut game_rng: ResMut<GameRng>,
+    mut shop: ResMut<Shop>,
+    upgrade_manager: Res<UpgradeManager>,
+    mut wave_events: EventReader<WaveCompleteEvent>,
+    player_query: Query<&Player>,
+) {
+    for event in wave_events.read() {
+        if let Ok(player) = player_query.get_single() {
+            // Priced for the wave coming up
+            shop.restock(&config, &upgrade_manager, &catalogue, player, event.wave_number + 1, game_rng.upgrades());
+            shop.open = true;
+        }
+    }
+}
+
+fn close_shop_system(
+    wave_manager: Res<WaveManager>,
+    mut shop: ResMut<Shop>,
+) {
+    if shop.open && wave_manager.wave_active {
+        shop.open = false;
+        shop.offers.clear();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn shop_purchase_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<ShopConfig>,
+    catalogue: Res<UpgradeCatalogue>,
+    mut game_rng: ResMut<GameRng>,
+    mut shop: ResMut<Shop>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
+    mut purchase_events: EventReader<ShopPurchaseEvent>,
+    mut player_query: Query<&mut Player>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+
+    // Clicks come in as events, the hotkeys are read here
+    let hotkey = SHOP_KEYS.iter().position(|key| keyboard_input.just_pressed(*key));
+    let slots: Vec<usize> = purchase_events.read().map(|event| event.slot).chain(hotkey).collect();
+
+    for slot in slots {
+        if !shop.open {
+            continue;
+        }
+        let Some(offer) = shop.offers.get(slot).cloned() else {
+            continue;
+        };
+        if offer.sold || player.credits < offer.price {
+            continue;
+        }
+
+        player.credits -= offer.price;
+        info!("Bought {} for {} credits", offer.item.name(), offer.price);
+
+        match offer.item {
+            ShopItem::Upgrade(upgrade) => {
+                upgrade_manager.apply_upgrade(&upgrade, &mut player);
+            }
+            ShopItem::Buff(power_up) => {
+                shop.bought_buffs.push(power_up);
+            }
+            ShopItem::Repair(amount) => {
+                player.health = (player.health + amount).min(player.max_health);
+            }
+            ShopItem::ShieldRefill => {
+                player.shields = player.max_shields;
+            }
+            ShopItem::Reroll => {
+                shop.rerolls += 1;
+                shop.reroll(&config, &upgrade_manager, &catalogue, &player, game_rng.upgrades());
+                continue; // Rerolls never sell out
+            }
+        }
+
+        shop.offers[slot].sold = true;
+    }
+}
+
+fn deliver_bought_buffs_system(
+    mut shop: ResMut<Shop>,
+    mut power_up_events: EventWriter<PowerUpCollectedEvent>,
+) {
+    for power_up in shop.bought_buffs.drain(..) {
+        power_up_events.send(PowerUpCollectedEvent { power_up, duration_scale: 1.0 });
+    }
+}
@@ -0,0 +1,163 @@
+//! Fixed-timestep simulation - gameplay ticks in `FixedUpdate` alongside Rapier
+//!
+//! Every tick runs the same chain: restore the simulated transforms, run gameplay, hand moved
+//! rigid bodies to Rapier, step physics, then capture the result. Rendering happens at whatever
+//! rate the display runs, so between ticks `InterpolatedTransform` entities are drawn part way
+//! from their previous tick to their current one.
+//!
+//! Gameplay inside a tick runs in a fixed order (`GameplaySet` stages, each plugin's systems
+//! chained within its stage). Left to the scheduler, the order of unrelated systems can change
+//! from one launch to the next, and with it the outcome of a seeded run.
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_rapier3d::prelude::*;
+use crate::config::GameConfig;
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .configure_sets(FixedUpdate, (
+                SimulationSet::Restore,
+                SimulationSet::Gameplay,
+                SimulationSet::SyncBodies,
+            ).chain().before(PhysicsSet::SyncBackend))
+            .configure_sets(FixedUpdate, (
+                GameplaySet::Spawning,
+                GameplaySet::Player,
+                GameplaySet::Status,
+                GameplaySet::Affixes,
+                GameplaySet::Enemies,
+                GameplaySet::Loot,
+                GameplaySet::Environment,
+                GameplaySet::Progression,
+            ).chain().in_set(SimulationSet::Gameplay))
+            .configure_sets(FixedUpdate, SimulationSet::Capture.after(PhysicsSet::Writeback))
+            .configure_sets(PostUpdate, SimulationSet::Interpolate.before(TransformSystem::TransformPropagate))
+            .add_systems(Startup, configure_tick_rate_system)
+            .add_systems(FixedUpdate, (
+                restore_transforms_system.in_set(SimulationSet::Restore),
+                sync_body_transforms_system.in_set(SimulationSet::SyncBodies),
+                capture_transforms_system.in_set(SimulationSet::Capture),
+            ))
+            .add_systems(PostUpdate, interpolate_transforms_system.in_set(SimulationSet::Interpolate));
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    Restore, // Undo last frame's interpolation before the tick reads any transform
+    Gameplay, // All gameplay systems, once per tick
+    SyncBodies, // Push gameplay transform edits to Rapier before it steps
+    Capture, // Record where everything ended up this tick
+    Interpolate, // Per-frame, blends the last two ticks for rendering
+}
+
+// Stages of a gameplay tick, in the order they run
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameplaySet {
+    Spawning, // Wave and survival spawners, telegraphs
+    Player,
+    Status, // Status effects tick before this tick's hits land
+    Affixes,
+    Enemies, // AI, attacks, hits and deaths
+    Loot, // Drops from this tick's deaths, pickups
+    Environment,
+    Progression, // Wave completion and director, after every kill is in
+}
+
+/// Draws an entity between its last two simulated transforms instead of snapping once per tick.
+/// Add it at spawn to anything that moves during the simulation.
+#[derive(Component, Default)]
+pub struct InterpolatedTransform {
+    pub previous: Transform,
+    pub current: Transform,
+    initialized: bool, // Set on the entity's first captured tick
+}
+
+impl InterpolatedTransform {
+    /// Jump straight to `transform` without blending in from the old position
+    pub fn snap(&mut self, transform: Transform) {
+        self.previous = transform;
+        self.current = transform;
+        self.initialized = true;
+    }
+}
+
+type RootBodyFilter = (With<RigidBody>, Without<Parent>);
+
+// Systems
+fn configure_tick_rate_system(
+    config: Res<GameConfig>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let tick_rate = if config.tick_rate > 0.0 {
+        config.tick_rate
+    } else {
+        warn!("tick_rate must be positive, falling back to {} Hz", GameConfig::default().tick_rate);
+        GameConfig::default().tick_rate
+    };
+
+    fixed_time.set_timestep_hz(tick_rate as f64);
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: 1.0 / tick_rate,
+        substeps: 1,
+    };
+    info!("Simulation running at {} ticks per second", tick_rate);
+}
+
+fn restore_transforms_system(
+    mut query: Query<(&mut Transform, &InterpolatedTransform)>,
+) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        if interpolated.initialized {
+            *transform = interpolated.current;
+        }
+    }
+}
+
+fn sync_body_transforms_system(
+    mut query: Query<(&Transform, &mut GlobalTransform), RootBodyFilter>,
+) {
+    // Transform propagation only runs once per frame, but Rapier reads `GlobalTransform`
+    // every tick, so a root body's global transform has to follow gameplay edits here
+    for (transform, mut global_transform) in query.iter_mut() {
+        let synced = GlobalTransform::from(*transform);
+        if *global_transform != synced {
+            *global_transform = synced;
+        }
+    }
+}
+
+fn capture_transforms_system(
+    mut query: Query<(&Transform, &mut InterpolatedTransform)>,
+) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        if interpolated.initialized {
+            interpolated.previous = interpolated.current;
+            interpolated.current = *transform;
+        } else {
+            interpolated.snap(*transform);
+        }
+    }
+}
+
+fn interpolate_transforms_system(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &InterpolatedTransform)>,
+) {
+    let alpha = fixed_time.overstep_percentage();
+
+    for (mut transform, interpolated) in query.iter_mut() {
+        if !interpolated.initialized {
+            continue;
+        }
+
+        let (previous, current) = (interpolated.previous, interpolated.current);
+        transform.translation = previous.translation.lerp(current.translation, alpha);
+        transform.rotation = previous.rotation.slerp(current.rotation, alpha);
+        transform.scale = previous.scale.lerp(current.scale, alpha);
+    }
+}
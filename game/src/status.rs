@@ -0,0 +1,169 @@
+//! Status effect system - damage over time, crowd control and damage modifiers
+use bevy::prelude::*;
+use crate::GameState;
+use crate::enemy::Enemy;
+use crate::player::Player;
+use crate::simulation::GameplaySet;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate, (
+                enemy_status_tick_system,
+                player_status_tick_system,
+            ).chain()
+             .in_set(GameplaySet::Status)
+             .run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusEffectType {
+    Burn,
+    Slow,
+    Freeze,
+    Shock,
+    Vulnerability,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub effect_type: StatusEffectType,
+    pub stacks: u32,
+    pub max_stacks: u32,
+    pub duration: f32,
+    pub remaining: f32,
+    pub tick_damage: f32, // Per stack, per tick
+    pub tick_interval: f32,
+    pub tick_timer: f32,
+}
+
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffect {
+    pub fn new(effect_type: StatusEffectType) -> Self {
+        let (max_stacks, duration, tick_damage, tick_interval) = match effect_type {
+            StatusEffectType::Burn => (5, 3.0, 5.0, 0.5),
+            StatusEffectType::Slow => (3, 2.0, 0.0, 0.0),
+            StatusEffectType::Freeze => (1, 1.0, 0.0, 0.0),
+            StatusEffectType::Shock => (3, 2.0, 8.0, 1.0),
+            StatusEffectType::Vulnerability => (5, 4.0, 0.0, 0.0),
+        };
+
+        Self {
+            effect_type,
+            stacks: 1,
+            max_stacks,
+            duration,
+            remaining: duration,
+            tick_damage,
+            tick_interval,
+            tick_timer: 0.0,
+        }
+    }
+}
+
+impl StatusEffects {
+    /// Adds an effect, stacking onto and refreshing an existing effect of the same type
+    pub fn apply(&mut self, effect: StatusEffect) {
+        if let Some(existing) = self.effects.iter_mut().find(|e| e.effect_type == effect.effect_type) {
+            existing.stacks = (existing.stacks + effect.stacks).min(existing.max_stacks);
+            existing.remaining = existing.remaining.max(effect.duration);
+        } else {
+            self.effects.push(effect);
+        }
+    }
+
+    pub fn has(&self, effect_type: StatusEffectType) -> bool {
+        self.effects.iter().any(|e| e.effect_type == effect_type)
+    }
+
+    pub fn stacks(&self, effect_type: StatusEffectType) -> u32 {
+        self.effects
+            .iter()
+            .find(|e| e.effect_type == effect_type)
+            .map_or(0, |e| e.stacks)
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.has(StatusEffectType::Freeze)
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_frozen() {
+            return 0.0;
+        }
+        (1.0 - self.stacks(StatusEffectType::Slow) as f32 * 0.2).max(0.2)
+    }
+
+    // Shock weakens outgoing damage
+    pub fn damage_dealt_multiplier(&self) -> f32 {
+        (1.0 - self.stacks(StatusEffectType::Shock) as f32 * 0.1).max(0.5)
+    }
+
+    pub fn damage_taken_multiplier(&self) -> f32 {
+        1.0 + self.stacks(StatusEffectType::Vulnerability) as f32 * 0.15
+    }
+
+    /// Advances every effect by `dt`, drops expired ones and returns the tick damage dealt
+    pub fn tick(&mut self, dt: f32) -> f32 {
+        let mut damage = 0.0;
+
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= dt;
+
+            if effect.tick_interval > 0.0 {
+                effect.tick_timer += dt;
+                while effect.tick_timer >= effect.tick_interval {
+                    effect.tick_timer -= effect.tick_interval;
+                    damage += effect.tick_damage * effect.stacks as f32;
+                }
+            }
+        }
+
+        self.effects.retain(|e| e.remaining > 0.0);
+        damage
+    }
+}
+
+// Systems
+fn enemy_status_tick_system(
+    time: Res<Time>,
+    mut query: Query<(&mut Enemy, &mut StatusEffects)>,
+) {
+    let current_time = time.elapsed_seconds();
+
+    for (mut enemy, mut status) in query.iter_mut() {
+        if status.effects.is_empty() {
+            continue;
+        }
+
+        let damage = status.tick(time.delta_seconds());
+        if damage > 0.0 {
+            let multiplier = status.damage_taken_multiplier();
+            enemy.take_damage(damage * multiplier, current_time);
+        }
+    }
+}
+
+pub fn player_status_tick_system(
+    time: Res<Time>,
+    mut query: Query<(&mut Player, &mut StatusEffects)>,
+) {
+    for (mut player, mut status) in query.iter_mut() {
+        if status.effects.is_empty() {
+            continue;
+        }
+
+        let damage = status.tick(time.delta_seconds());
+        if damage > 0.0 {
+            let multiplier = status.damage_taken_multiplier();
+            player.take_damage(damage * multiplier);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Survival mode - continuous timeline-driven spawning until the clock runs out
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{GameData, GameMode, GameState};
+use crate::asteroid::Asteroid;
+use crate::config::WaveConfig;
+use crate::enemy::{spawn_enemy_with_modifiers, Enemy, EnemyModifiers, EnemyType};
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::simulation::GameplaySet;
+use crate::wave::{resolve_spawn_position, AIDirector};
+
+pub struct SurvivalPlugin;
+
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SurvivalTimeline>()
+            .init_resource::<SurvivalRun>()
+            .add_systems(Startup, load_survival_timeline)
+            .add_systems(OnEnter(GameState::Playing), initialize_survival_system
+                .run_if(resource_equals(GameMode::Survival)))
+            .add_systems(FixedUpdate, (
+                survival_clock_system,
+                survival_spawn_system,
+                mini_boss_system,
+                survival_victory_system,
+            ).chain()
+             .after(crate::wave::spawn_telegraph_system)
+             .in_set(GameplaySet::Spawning)
+             .run_if(in_state(GameState::Playing))
+             .run_if(resource_equals(GameMode::Survival)));
+    }
+}
+
+// Survival timeline, loaded from config/survival.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct SurvivalTimeline {
+    pub duration: f32, // Seconds the player has to survive
+    pub spawn_radius: f32, // Distance from the player that enemies appear at
+    pub phases: Vec<SpawnPhase>,
+    #[serde(default)]
+    pub mini_bosses: Vec<MiniBossEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpawnPhase {
+    pub start: f32,
+    pub end: f32,
+    // Spawn rate ramps linearly from start to end across the phase
+    pub spawns_per_second_start: f32,
+    pub spawns_per_second_end: f32,
+    pub max_alive: u32,
+    pub enemies: Vec<WeightedEnemy>,
+    #[serde(default)]
+    pub modifiers: EnemyModifiers,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WeightedEnemy {
+    pub enemy_type: EnemyType,
+    pub weight: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MiniBossEvent {
+    pub time: f32,
+    pub enemy_type: EnemyType,
+    #[serde(default = "default_boss_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub modifiers: EnemyModifiers,
+}
+
+fn default_boss_count() -> u32 {
+    1
+}
+
+impl Default for SurvivalTimeline {
+    fn default() -> Self {
+        let weighted = |enemies: &[(EnemyType, f32)]| {
+            enemies
+                .iter()
+                .map(|(enemy_type, weight)| WeightedEnemy { enemy_type: enemy_type.clone(), weight: *weight })
+                .collect()
+        };
+
+        Self {
+            duration: 600.0,
+            spawn_radius: 300.0,
+            phases: vec![
+                SpawnPhase {
+                    start: 0.0,
+                    end: 120.0,
+                    spawns_per_second_start: 0.5,
+                    spawns_per_second_end: 1.0,
+                    max_alive: 40,
+                    enemies: weighted(&[(EnemyType::Chaser, 3.0), (EnemyType::Swarm, 1.0)]),
+                    modifiers: EnemyModifiers::default(),
+                },
+                SpawnPhase {
+                    start: 120.0,
+                    end: 300.0,
+                    spawns_per_second_start: 1.0,
+                    spawns_per_second_end: 2.0,
+                    max_alive: 70,
+                    enemies: weighted(&[(EnemyType::Chaser, 2.0), (EnemyType::Swarm, 2.0), (EnemyType::Shooter, 1.0)]),
+                    modifiers: EnemyModifiers::default(),
+                },
+                SpawnPhase {
+                    start: 300.0,
+                    end: 600.0,
+                    spawns_per_second_start: 2.0,
+                    spawns_per_second_end: 4.0,
+                    max_alive: 100,
+                    enemies: weighted(&[(EnemyType::Swarm, 3.0), (EnemyType::Shooter, 2.0), (EnemyType::Tank, 1.0), (EnemyType::Elite, 0.5)]),
+                    modifiers: EnemyModifiers::default(),
+                },
+            ],
+            mini_bosses: vec![
+                MiniBossEvent { time: 180.0, enemy_type: EnemyType::Elite, count: 3, modifiers: EnemyModifiers::default() },
+                MiniBossEvent { time: 420.0, enemy_type: EnemyType::Boss, count: 1, modifiers: EnemyModifiers::default() },
+            ],
+        }
+    }
+}
+
+impl SurvivalTimeline {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let timeline_str = std::fs::read_to_string("config/survival.ron")?;
+        let timeline: SurvivalTimeline = ron::de::from_str(&timeline_str)?;
+        Ok(timeline)
+    }
+
+    /// The phase covering `time`, holding on the last one if the timeline runs past its phases
+    pub fn phase_at(&self, time: f32) -> Option<&SpawnPhase> {
+        self.phases
+            .iter()
+            .find(|phase| time >= phase.start && time < phase.end)
+            .or_else(|| self.phases.last().filter(|phase| time >= phase.end))
+    }
+}
+
+impl SpawnPhase {
+    pub fn spawns_per_second(&self, time: f32) -> f32 {
+        let length = (self.end - self.start).max(f32::EPSILON);
+        let t = ((time - self.start) / length).clamp(0.0, 1.0);
+        self.spawns_per_second_start + (self.spawns_per_second_end - self.spawns_per_second_start) * t
+    }
+
+    pub fn pick_enemy(&self, rng: &mut impl Rng) -> Option<EnemyType> {
+        let total: f32 = self.enemies.iter().map(|enemy| enemy.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        for enemy in &self.enemies {
+            if roll < enemy.weight {
+                return Some(enemy.enemy_type.clone());
+            }
+            roll -= enemy.weight;
+        }
+        self.enemies.last().map(|enemy| enemy.enemy_type.clone())
+    }
+}
+
+// Progress through the current survival run
+#[derive(Resource, Default)]
+pub struct SurvivalRun {
+    pub elapsed: f32,
+    pub spawn_accumulator: f32,
+    pub next_mini_boss: usize,
+    pub enemies_spawned: u32,
+    pub victory: bool,
+}
+
+impl SurvivalRun {
+    pub fn remaining(&self, timeline: &SurvivalTimeline) -> f32 {
+        (timeline.duration - self.elapsed).max(0.0)
+    }
+}
+
+// Systems
+fn load_survival_timeline(mut timeline: ResMut<SurvivalTimeline>) {
+    match SurvivalTimeline::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded survival timeline ({} phases, {}s)", loaded.phases.len(), loaded.duration);
+            *timeline = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/survival.ron ({}), using the default timeline", err);
+        }
+    }
+}
+
+fn initialize_survival_system(
+    timeline: Res<SurvivalTimeline>,
+    run: Res<SurvivalRun>,
+) {
+    // Every new run starts from a fresh `SurvivalRun`; resuming from pause keeps the clock
+    if run.elapsed == 0.0 {
+        info!("Survival mode - survive for {:.0} seconds", timeline.duration);
+    }
+}
+
+fn survival_clock_system(
+    time: Res<Time>,
+    mut run: ResMut<SurvivalRun>,
+    mut game_data: ResMut<GameData>,
+) {
+    if run.victory {
+        return;
+    }
+
+    run.elapsed += time.delta_seconds();
+    game_data.game_time = run.elapsed;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn survival_spawn_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    timeline: Res<SurvivalTimeline>,
+    config: Res<WaveConfig>,
+    director: Res<AIDirector>,
+    mut game_rng: ResMut<GameRng>,
+    mut run: ResMut<SurvivalRun>,
+    enemy_query: Query<(), With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+    asteroid_query: Query<(&Transform, &Asteroid)>,
+) {
+    let rng = game_rng.spawns();
+
+    if run.victory {
+        return;
+    }
+
+    let Some(phase) = timeline.phase_at(run.elapsed) else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    // The director scales density the same way it paces scripted waves
+    let pace = if director.enabled { director.intensity } else { 1.0 };
+    run.spawn_accumulator += phase.spawns_per_second(run.elapsed) * pace * time.delta_seconds();
+
+    let alive = enemy_query.iter().count() as u32;
+    let budget = phase.max_alive.saturating_sub(alive);
+    let to_spawn = (run.spawn_accumulator.floor() as u32).min(budget);
+    run.spawn_accumulator -= run.spawn_accumulator.floor();
+
+    if to_spawn == 0 {
+        return;
+    }
+
+    let player_position = player_transform.translation;
+    let asteroids: Vec<(Vec3, f32)> = asteroid_query
+        .iter()
+        .map(|(transform, asteroid)| (transform.translation, asteroid.size * 0.5 + 20.0))
+        .collect();
+
+    for _ in 0..to_spawn {
+        let Some(enemy_type) = phase.pick_enemy(rng) else {
+            break;
+        };
+
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let position = resolve_spawn_position(
+            Vec3::new(
+                player_position.x + angle.cos() * timeline.spawn_radius,
+                0.0,
+                player_position.z + angle.sin() * timeline.spawn_radius,
+            ),
+            Some(player_position),
+            &asteroids,
+            config.min_spawn_distance,
+        );
+
+        let enemy_type = director.adjust_enemy_type(enemy_type, rng);
+        spawn_enemy_with_modifiers(
+            &mut commands,
+            &asset_server,
+            rng,
+            enemy_type,
+            position,
+            &phase.modifiers,
+        );
+        run.enemies_spawned += 1;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mini_boss_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    timeline: Res<SurvivalTimeline>,
+    config: Res<WaveConfig>,
+    mut game_rng: ResMut<GameRng>,
+    mut run: ResMut<SurvivalRun>,
+    player_query: Query<&Transform, With<Player>>,
+    asteroid_query: Query<(&Transform, &Asteroid)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    while let Some(event) = timeline.mini_bosses.get(run.next_mini_boss) {
+        if event.time > run.elapsed {
+            break;
+        }
+        run.next_mini_boss += 1;
+
+        info!("Mini-boss event at {:.0}s: {} {:?}", event.time, event.count, event.enemy_type);
+
+        let player_position = player_transform.translation;
+        let asteroids: Vec<(Vec3, f32)> = asteroid_query
+            .iter()
+            .map(|(transform, asteroid)| (transform.translation, asteroid.size * 0.5 + 20.0))
+            .collect();
+
+        // Bosses close in from evenly spaced points around the player
+        for i in 0..event.count {
+            let angle = i as f32 / event.count as f32 * std::f32::consts::TAU;
+            let position = resolve_spawn_position(
+                Vec3::new(
+                    player_position.x + angle.cos() * timeline.spawn_radius,
+                    0.0,
+                    player_position.z + angle.sin() * timeline.spawn_radius,
+                ),
+                Some(player_position),
+                &asteroids,
+                config.min_spawn_distance,
+            );
+            spawn_enemy_with_modifiers(
+                &mut commands,
+                &asset_server,
+                game_rng.spawns(),
+                event.enemy_type.clone(),
+                position,
+                &event.modifiers,
+            );
+            run.enemies_spawned += 1;
+        }
+    }
+}
+
+fn survival_victory_system(
+    timeline: Res<SurvivalTimeline>,
+    mut run: ResMut<SurvivalRun>,
+    mut game_data: ResMut<GameData>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if run.victory || run.elapsed < timeline.duration {
+        return;
+    }
+
+    run.victory = true;
+    game_data.score += 1000 + timeline.duration as u32 * 10;
+    info!("Survived {:.0} seconds! Final score: {}", timeline.duration, game_data.score);
+    next_state.set(GameState::GameOver);
+}
@@ -0,0 +1,152 @@
+//! Test support - a headless game that tests drive one fixed tick at a time
+//!
+//! `TestApp::new()` starts a wave run, then clears the arena and holds wave 1 open with nothing
+//! left to spawn, so a test only sees the entities it spawns itself.
+use std::time::Duration;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use crate::{GameEntity, GameMode, GameState, VoidSurvivorPlugin};
+use crate::enemy::{spawn_enemy, EnemyType};
+use crate::loot::{spawn_loot, LootAssets, LootType, Rarity};
+use crate::player::{spawn_player_ship, spawn_projectile, WeaponStats, WeaponType};
+use crate::rng::GameRng;
+use crate::wave::WaveManager;
+
+pub struct TestApp {
+    pub app: App,
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app
+            .add_plugins(VoidSurvivorPlugin::headless())
+            .insert_resource(GameMode::Waves)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+
+        // Startup and entering Playing, without any time passing
+        app.update();
+
+        // From here on every update is exactly one fixed tick
+        let timestep = app.world.resource::<Time<Fixed>>().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+        let mut test_app = Self { app };
+        test_app.clear_arena();
+        test_app
+    }
+
+    /// Despawns every game entity and holds the current wave open with nothing left to spawn,
+    /// so it neither completes nor sends anything new in.
+    pub fn clear_arena(&mut self) {
+        let entities: Vec<Entity> = self.app.world
+            .query_filtered::<Entity, With<GameEntity>>()
+            .iter(&self.app.world)
+            .collect();
+        for entity in entities {
+            if let Some(entity) = self.app.world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+        }
+
+        let mut wave_manager = self.app.world.resource_mut::<WaveManager>();
+        wave_manager.pending_groups.clear();
+        // Counted as under way, so coming back from a pause or level-up doesn't restart wave 1
+        wave_manager.enemies_spawned = 1;
+        wave_manager.enemies_to_spawn = 2;
+        wave_manager.wave_active = true;
+    }
+
+    /// Goes back to the main menu and starts a new run from there, the way a player would after
+    /// a game over. Unlike `new()` it leaves the arena as the run sets it up.
+    pub fn start_new_run(&mut self) {
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::MainMenu);
+        self.tick();
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+        self.tick();
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.app.insert_resource(GameRng::new(seed));
+        self
+    }
+
+    pub fn spawn_player(&mut self, position: Vec3) -> Entity {
+        self.app.world.run_system_once(move |mut commands: Commands, asset_server: Res<AssetServer>| {
+            spawn_player_ship(&mut commands, &asset_server, position)
+        })
+    }
+
+    pub fn spawn_enemy(&mut self, enemy_type: EnemyType, position: Vec3) -> Entity {
+        self.app.world.run_system_once(move |mut commands: Commands, asset_server: Res<AssetServer>, mut game_rng: ResMut<GameRng>| {
+            spawn_enemy(&mut commands, &asset_server, game_rng.spawns(), enemy_type.clone(), position)
+        })
+    }
+
+    /// A shot from `owner` (usually the player) travelling along `direction`
+    pub fn spawn_projectile(&mut self, owner: Entity, position: Vec3, direction: Vec3, damage: f32) -> Entity {
+        self.app.world.run_system_once(move |mut commands: Commands, asset_server: Res<AssetServer>| {
+            let stats = WeaponStats { damage, ..WeaponType::Blaster.stats(1) };
+            spawn_projectile(&mut commands, &asset_server, position, direction, &WeaponType::Blaster, &stats, &[], owner)
+        })
+    }
+
+    pub fn spawn_loot(&mut self, loot_type: LootType, position: Vec3, value: f32) -> Entity {
+        self.spawn_loot_of_rarity(loot_type, Rarity::Common, position, value)
+    }
+
+    pub fn spawn_loot_of_rarity(&mut self, loot_type: LootType, rarity: Rarity, position: Vec3, value: f32) -> Entity {
+        self.app.world.run_system_once(move |mut commands: Commands, loot_assets: Res<LootAssets>| {
+            spawn_loot(&mut commands, &loot_assets, loot_type.clone(), rarity, position, value)
+        })
+    }
+
+    pub fn tick(&mut self) {
+        self.app.update();
+    }
+
+    pub fn advance_ticks(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.tick();
+        }
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.app.world.get::<T>(entity)
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
+        self.app.world.get_mut::<T>(entity)
+    }
+
+    /// Every `T` currently in the world
+    pub fn all<T: Component>(&mut self) -> Vec<&T> {
+        let mut query = self.app.world.query::<&T>();
+        query.iter(&self.app.world).collect()
+    }
+
+    pub fn exists(&self, entity: Entity) -> bool {
+        self.app.world.get_entity(entity).is_some()
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world.resource::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.app.world.resource_mut::<R>()
+    }
+
+    /// Events of type `E` sent during the last tick
+    pub fn events<E: Event>(&self) -> Vec<&E> {
+        self.app.world.resource::<Events<E>>().iter_current_update_events().collect()
+    }
+}
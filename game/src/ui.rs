@@ -7,9 +7,16 @@
 //! http://creativecommons.org/licenses/by-sa/3.0/
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::Velocity;
-use crate::{GameState};
-use crate::player::Player;
-use crate::wave::WaveManager;
+use crate::{GameMode, GameState};
+use crate::player::{Player, WeaponGainedEvent};
+use crate::powerup::ActiveBuff;
+use crate::replay::ReplayPlayback;
+use crate::rng::GameRng;
+use crate::shop::{Shop, ShopPurchaseEvent, SHOP_SLOTS};
+use crate::config::WaveConfig;
+use crate::upgrade::{UpgradeBanishEvent, UpgradeCatalogue, UpgradeChosenEvent, UpgradeHighlightEvent, UpgradeManager, UpgradeRerollEvent};
+use crate::survival::{SurvivalRun, SurvivalTimeline};
+use crate::wave::{AIDirector, WaveManager};
 
 pub struct UIPlugin;
 
@@ -20,12 +27,34 @@ impl Plugin for UIPlugin {
             .add_systems(Update, (
                 update_health_bar,
                 update_wave_info,
+                update_wave_banner,
+                update_break_panel,
                 update_player_stats,
+                update_buff_bar,
+                update_weapon_card,
+                update_shop_panel,
                 update_debug_display,
             ).run_if(in_state(GameState::Playing)))
+            // Like the level-up buttons: bought on the frame they're clicked, and replays bring
+            // their own clicks
+            .add_systems(Update, shop_button_system
+                .before(crate::shop::shop_purchase_system)
+                .run_if(in_state(GameState::Playing))
+                .run_if(not(resource_exists::<ReplayPlayback>())))
+            .add_systems(OnEnter(GameState::LevelUp), setup_level_up_screen)
+            .add_systems(Update, level_up_card_system.run_if(in_state(GameState::LevelUp)))
+            // Clicks land on the same frame's upgrade pick. A replay feeds its recorded clicks in
+            // instead, so the real mouse is ignored while watching one.
+            .add_systems(Update, level_up_button_system
+                .before(crate::upgrade::upgrade_selection_system)
+                .run_if(in_state(GameState::LevelUp))
+                .run_if(not(resource_exists::<ReplayPlayback>())))
+            .add_systems(OnExit(GameState::LevelUp), cleanup_level_up_screen)
             .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
             .add_systems(Update, main_menu_system.run_if(in_state(GameState::MainMenu)))
-            .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu);
+            .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu)
+            .add_systems(OnEnter(GameState::GameOver), setup_death_screen)
+            .add_systems(OnExit(GameState::GameOver), cleanup_death_screen);
     }
 }
 
@@ -45,20 +74,87 @@ pub struct WaveText;
 #[derive(Component)]
 pub struct LevelText;
 
+#[derive(Component)]
+pub struct IntensityText;
+
+#[derive(Component)]
+pub struct CreditsText;
+
+// Break-time shop, one button per slot
+#[derive(Component)]
+pub struct ShopPanel;
+
+#[derive(Component)]
+pub struct ShopButton {
+    pub slot: usize,
+}
+
+#[derive(Component)]
+pub struct ShopSlotText {
+    pub slot: usize,
+}
+
+#[derive(Component)]
+pub struct WaveClearedBanner;
+
+#[derive(Component)]
+pub struct BreakPanelText;
+
 #[derive(Component)]
 pub struct ExperienceBar;
 
+// Row of timer icons for the running power-ups
+#[derive(Component)]
+pub struct BuffBar;
+
+// Each icon part points at the `ActiveBuff` entity it shows
+#[derive(Component)]
+pub struct BuffIcon {
+    pub buff: Entity,
+}
+
+#[derive(Component)]
+pub struct BuffIconText {
+    pub buff: Entity,
+}
+
+#[derive(Component)]
+pub struct BuffIconTimer {
+    pub buff: Entity,
+}
+
+// Card announcing a weapon unlock or level-up, hidden once `remaining` runs out
+#[derive(Component)]
+pub struct WeaponCard {
+    pub remaining: f32,
+}
+
 #[derive(Component)]
 pub struct MainMenuUI;
 
+#[derive(Component)]
+pub struct DeathScreenUI;
+
 #[derive(Component)]
 pub struct DebugDisplay;
 
 #[derive(Component)]
 pub struct PlayButton;
 
+#[derive(Component)]
+pub struct SurvivalButton;
+
 // Game UI Setup
-fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_game_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    existing: Query<(), With<GameUI>>,
+) {
+    // The HUD survives pauses and level-ups, so don't build a second one on resume
+    if !existing.is_empty() {
+        return;
+    }
+    
     // Root UI node
     commands
         .spawn((
@@ -102,6 +198,32 @@ fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         WaveText,
                     ));
                     
+                    // Director intensity
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "Intensity: 1.0x",
+                            TextStyle {
+                                font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                                font_size: 18.0,
+                                color: Color::ORANGE,
+                            },
+                        ),
+                        IntensityText,
+                    ));
+                    
+                    // Credits
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "Credits: 0",
+                            TextStyle {
+                                font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                                font_size: 24.0,
+                                color: Color::GOLD,
+                            },
+                        ),
+                        CreditsText,
+                    ));
+                    
                     // Level info
                     parent.spawn((
                         TextBundle::from_section(
@@ -219,6 +341,156 @@ fn setup_game_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         });
                 });
             
+            // Wave cleared banner (centre screen, shown during breaks)
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        top: Val::Percent(25.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("ui/Woodwarrior-Bold.otf"),
+                                font_size: 32.0,
+                                color: Color::GOLD,
+                            },
+                        ).with_text_alignment(TextAlignment::Center),
+                        WaveClearedBanner,
+                    ));
+                    
+                    // Break countdown, ready prompt and upgrade pick
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                                font_size: 20.0,
+                                color: Color::WHITE,
+                            },
+                        ).with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            margin: UiRect::top(Val::Px(20.0)),
+                            ..default()
+                        }),
+                        BreakPanelText,
+                    ));
+                });
+            
+            // Weapon pickup card (above the bottom bar)
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        bottom: Val::Px(100.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                                font_size: 20.0,
+                                color: Color::rgb(1.0, 0.6, 0.2),
+                            },
+                        ).with_text_alignment(TextAlignment::Center)
+                        .with_style(Style {
+                            padding: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        })
+                        .with_background_color(Color::rgba(0.0, 0.0, 0.0, 0.7)),
+                        WeaponCard { remaining: 0.0 },
+                    ));
+                });
+            
+            // Shop (right side, open during wave breaks)
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(240.0),
+                            right: Val::Px(10.0),
+                            width: Val::Px(300.0),
+                            padding: UiRect::all(Val::Px(10.0)),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    ShopPanel,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Shop",
+                        TextStyle {
+                            font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                            font_size: 24.0,
+                            color: Color::GOLD,
+                        },
+                    ));
+                    
+                    for slot in 0..SHOP_SLOTS {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0),
+                                        margin: UiRect::top(Val::Px(6.0)),
+                                        padding: UiRect::all(Val::Px(6.0)),
+                                        ..default()
+                                    },
+                                    background_color: Color::rgb(0.2, 0.4, 0.8).into(),
+                                    ..default()
+                                },
+                                ShopButton { slot },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        "",
+                                        TextStyle {
+                                            font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                                            font_size: 16.0,
+                                            color: Color::WHITE,
+                                        },
+                                    ),
+                                    ShopSlotText { slot },
+                                ));
+                            });
+                    }
+                });
+            
+            // Power-up timers (under the top bar, left side)
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(70.0),
+                        left: Val::Px(10.0),
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                },
+                BuffBar,
+            ));
+            
             // Debug panel (top-right corner)
             parent
                 .spawn(NodeBundle {
@@ -308,6 +580,34 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                         },
                     ));
                 });
+            
+            // Survival button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.0),
+                            height: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(20.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.2, 0.4, 0.8).into(),
+                        ..default()
+                    },
+                    SurvivalButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Survival",
+                        TextStyle {
+                            font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                            font_size: 24.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
         });
 }
 
@@ -333,11 +633,88 @@ fn update_health_bar(
 }
 
 fn update_wave_info(
+    game_mode: Res<GameMode>,
     wave_manager: Res<WaveManager>,
-    mut wave_text_query: Query<&mut Text, With<WaveText>>,
+    survival_run: Res<SurvivalRun>,
+    survival_timeline: Res<SurvivalTimeline>,
+    director: Res<AIDirector>,
+    mut wave_text_query: Query<&mut Text, (With<WaveText>, Without<IntensityText>)>,
+    mut intensity_text_query: Query<&mut Text, With<IntensityText>>,
 ) {
     if let Ok(mut text) = wave_text_query.get_single_mut() {
-        text.sections[0].value = format!("Wave: {}", wave_manager.current_wave);
+        text.sections[0].value = match *game_mode {
+            GameMode::Waves => format!("Wave: {}", wave_manager.current_wave),
+            GameMode::Survival => {
+                let remaining = survival_run.remaining(&survival_timeline).ceil() as u32;
+                format!("Survive: {}:{:02}", remaining / 60, remaining % 60)
+            }
+        };
+    }
+    
+    if let Ok(mut text) = intensity_text_query.get_single_mut() {
+        text.sections[0].value = if director.enabled {
+            format!("Intensity: {:.1}x", director.intensity)
+        } else {
+            "Intensity: Fixed".to_string()
+        };
+    }
+}
+
+fn update_wave_banner(
+    wave_manager: Res<WaveManager>,
+    mut banner_query: Query<(&mut Text, &mut Visibility), With<WaveClearedBanner>>,
+) {
+    if let Ok((mut text, mut visibility)) = banner_query.get_single_mut() {
+        match (&wave_manager.last_reward, wave_manager.wave_active) {
+            (Some(reward), false) => {
+                let mut lines = vec![
+                    format!("Wave {} Cleared!", reward.wave_number),
+                    format!("+{} XP  +Loot Chest", reward.bonus_experience as u32),
+                ];
+                if reward.fast_clear_bonus > 0 {
+                    lines.push(format!("Fast Clear +{}", reward.fast_clear_bonus));
+                }
+                if reward.flawless_bonus > 0 {
+                    lines.push(format!("Flawless +{}", reward.flawless_bonus));
+                }
+                
+                text.sections[0].value = lines.join("\n");
+                *visibility = Visibility::Inherited;
+            }
+            _ => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+fn update_break_panel(
+    wave_manager: Res<WaveManager>,
+    upgrade_manager: Res<UpgradeManager>,
+    config: Res<WaveConfig>,
+    mut panel_query: Query<(&mut Text, &mut Visibility), With<BreakPanelText>>,
+) {
+    if let Ok((mut text, mut visibility)) = panel_query.get_single_mut() {
+        if wave_manager.wave_active {
+            *visibility = Visibility::Hidden;
+            return;
+        }
+        
+        let mut lines = vec![
+            format!("Next wave in {}s", wave_manager.break_remaining().ceil() as u32),
+            format!("[Enter] Ready up (+{})", wave_manager.skip_bonus(&config)),
+        ];
+        
+        if upgrade_manager.upgrade_selection_active {
+            lines.push(String::new());
+            lines.push("Pick an upgrade:".to_string());
+            for (i, upgrade) in upgrade_manager.available_upgrades.iter().enumerate() {
+                lines.push(format!("[{}] {} - {}", i + 1, upgrade.name, upgrade.description));
+            }
+        }
+        
+        text.sections[0].value = lines.join("\n");
+        *visibility = Visibility::Inherited;
     }
 }
 
@@ -361,6 +738,204 @@ fn update_player_stats(
     }
 }
 
+fn update_buff_bar(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    buff_query: Query<(Entity, &ActiveBuff)>,
+    bar_query: Query<Entity, With<BuffBar>>,
+    icon_query: Query<(Entity, &BuffIcon)>,
+    mut text_query: Query<(&BuffIconText, &mut Text)>,
+    mut timer_query: Query<(&BuffIconTimer, &mut Style)>,
+) {
+    let Ok(bar) = bar_query.get_single() else {
+        return;
+    };
+    
+    // Drop icons for buffs that wore off
+    for (icon, buff_icon) in icon_query.iter() {
+        if !buff_query.contains(buff_icon.buff) {
+            commands.entity(icon).despawn_recursive();
+        }
+    }
+    
+    // Add icons for new buffs
+    for (buff_entity, buff) in buff_query.iter() {
+        if icon_query.iter().any(|(_, icon)| icon.buff == buff_entity) {
+            continue;
+        }
+        
+        let color = buff.source.color();
+        let icon = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(64.0),
+                        margin: UiRect::right(Val::Px(6.0)),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    border_color: color.into(),
+                    ..default()
+                },
+                BuffIcon { buff: buff_entity },
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    buff.source.icon_label(),
+                    TextStyle {
+                        font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                        font_size: 20.0,
+                        color,
+                    },
+                ));
+                
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                            font_size: 14.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    BuffIconText { buff: buff_entity },
+                ));
+                
+                // Timer bar
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(4.0),
+                            margin: UiRect::top(Val::Px(2.0)),
+                            ..default()
+                        },
+                        background_color: Color::DARK_GRAY.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: color.into(),
+                                ..default()
+                            },
+                            BuffIconTimer { buff: buff_entity },
+                        ));
+                    });
+            })
+            .id();
+        commands.entity(bar).add_child(icon);
+    }
+    
+    for (icon_text, mut text) in text_query.iter_mut() {
+        if let Ok((_, buff)) = buff_query.get(icon_text.buff) {
+            let seconds = buff.remaining.ceil() as u32;
+            text.sections[0].value = if buff.stacks > 1 {
+                format!("{}s x{}", seconds, buff.stacks)
+            } else {
+                format!("{}s", seconds)
+            };
+        }
+    }
+    
+    for (icon_timer, mut style) in timer_query.iter_mut() {
+        if let Ok((_, buff)) = buff_query.get(icon_timer.buff) {
+            let fraction = if buff.duration > 0.0 { buff.remaining / buff.duration } else { 0.0 };
+            style.width = Val::Percent(fraction.clamp(0.0, 1.0) * 100.0);
+        }
+    }
+}
+
+fn update_shop_panel(
+    shop: Res<Shop>,
+    player_query: Query<&Player>,
+    mut credits_text_query: Query<&mut Text, With<CreditsText>>,
+    mut panel_query: Query<&mut Visibility, With<ShopPanel>>,
+    mut button_query: Query<(&ShopButton, &Interaction, &mut BackgroundColor)>,
+    mut slot_text_query: Query<(&ShopSlotText, &mut Text), Without<CreditsText>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    
+    if let Ok(mut text) = credits_text_query.get_single_mut() {
+        text.sections[0].value = format!("Credits: {}", player.credits);
+    }
+    
+    if let Ok(mut visibility) = panel_query.get_single_mut() {
+        *visibility = if shop.open { Visibility::Inherited } else { Visibility::Hidden };
+    }
+    if !shop.open {
+        return;
+    }
+    
+    for (slot_text, mut text) in slot_text_query.iter_mut() {
+        text.sections[0].value = match shop.offers.get(slot_text.slot) {
+            Some(offer) if offer.sold => format!("[{}] {} - SOLD", slot_text.slot + 4, offer.item.name()),
+            Some(offer) => format!("[{}] {} - {}c\n{}", slot_text.slot + 4, offer.item.name(), offer.price, offer.item.description()),
+            None => String::new(),
+        };
+    }
+    
+    // Sold and unaffordable offers are greyed out
+    for (button, interaction, mut color) in button_query.iter_mut() {
+        let affordable = shop.offers
+            .get(button.slot)
+            .is_some_and(|offer| !offer.sold && player.credits >= offer.price);
+        *color = match (affordable, interaction) {
+            (false, _) => Color::rgb(0.25, 0.25, 0.25).into(),
+            (true, Interaction::Hovered | Interaction::Pressed) => Color::rgb(0.3, 0.5, 0.9).into(),
+            (true, Interaction::None) => Color::rgb(0.2, 0.4, 0.8).into(),
+        };
+    }
+}
+
+fn shop_button_system(
+    interaction_query: Query<(&Interaction, &ShopButton), Changed<Interaction>>,
+    mut purchase_events: EventWriter<ShopPurchaseEvent>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            purchase_events.send(ShopPurchaseEvent { slot: button.slot });
+        }
+    }
+}
+
+fn update_weapon_card(
+    time: Res<Time>,
+    mut weapon_events: EventReader<WeaponGainedEvent>,
+    mut card_query: Query<(&mut Text, &mut Visibility, &mut WeaponCard)>,
+) {
+    let Ok((mut text, mut visibility, mut card)) = card_query.get_single_mut() else {
+        return;
+    };
+    
+    // The latest pickup replaces whatever the card was showing
+    if let Some(event) = weapon_events.read().last() {
+        let weapon = &event.weapon_type;
+        text.sections[0].value = if weapon.is_evolved() {
+            format!("Evolved: {}\n{}", weapon.name(), weapon.description())
+        } else if event.level == 1 {
+            format!("New weapon: {}\n{}", weapon.name(), weapon.description())
+        } else {
+            format!("{} level {}\n{}", weapon.name(), event.level, weapon.level_up_summary(event.level))
+        };
+        card.remaining = 3.0;
+    }
+    
+    card.remaining -= time.delta_seconds();
+    *visibility = if card.remaining > 0.0 { Visibility::Inherited } else { Visibility::Hidden };
+}
+
 fn update_debug_display(
     player_query: Query<(&Transform, &Velocity), With<Player>>,
     mut debug_text_query: Query<&mut Text, With<DebugDisplay>>,
@@ -390,17 +965,19 @@ fn update_debug_display(
     }
 }
 
+type MenuButtonFilter = (Changed<Interaction>, Or<(With<PlayButton>, With<SurvivalButton>)>);
+
 // Menu Systems
 fn main_menu_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<PlayButton>),
-    >,
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, Has<SurvivalButton>), MenuButtonFilter>,
+    mut game_mode: ResMut<GameMode>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
+    for (interaction, mut color, survival) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
+                *game_mode = if survival { GameMode::Survival } else { GameMode::Waves };
+                info!("Starting game in {:?} mode", *game_mode);
                 next_state.set(GameState::Playing);
             }
             Interaction::Hovered => {
@@ -423,48 +1000,328 @@ fn cleanup_main_menu(
     }
 }
 
-// Upgrade selection UI (for later implementation)
+// Level-up screen UI
 #[derive(Component)]
 pub struct UpgradeSelectionUI;
 
+// One card per level-up offer, `index` into `UpgradeManager::level_up_offers`
 #[derive(Component)]
 pub struct UpgradeButton {
-    pub upgrade_type: UpgradeType,
+    pub index: usize,
+}
+
+// Card text refreshed from the offers, which change on a reroll or banish
+#[derive(Component)]
+pub struct UpgradeCardText {
+    pub index: usize,
+    pub field: UpgradeCardField,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UpgradeCardField {
+    Name,
+    Rarity,
+    Description,
+}
+
+#[derive(Component)]
+pub struct BanishButton {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct RerollButton;
+
+#[derive(Component)]
+pub struct LevelUpChargesText;
+
+fn setup_level_up_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    upgrade_manager: Res<UpgradeManager>,
+) {
+    let text_style = |font: &'static str, font_size: f32, color: Color| TextStyle {
+        font: asset_server.load(font),
+        font_size,
+        color,
+    };
+    
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            UpgradeSelectionUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Level Up!",
+                text_style("ui/Woodwarrior-Bold.otf", 48.0, Color::WHITE),
+            ).with_style(Style {
+                margin: UiRect::bottom(Val::Px(40.0)),
+                ..default()
+            }));
+            
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    // Banishing can leave fewer cards than this, the extras hide themselves
+                    for index in 0..upgrade_manager.level_up_offers.len() {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(220.0),
+                                        height: Val::Px(280.0),
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::Center,
+                                        row_gap: Val::Px(12.0),
+                                        padding: UiRect::all(Val::Px(15.0)),
+                                        ..default()
+                                    },
+                                    background_color: Color::rgb(0.2, 0.4, 0.8).into(),
+                                    ..default()
+                                },
+                                UpgradeButton { index },
+                            ))
+                            .with_children(|parent| {
+                                for (field, font, font_size) in [
+                                    (UpgradeCardField::Name, "ui/Woodwarrior-Bold.otf", 24.0),
+                                    (UpgradeCardField::Rarity, "ui/Woodwarrior-Regular.otf", 18.0),
+                                    (UpgradeCardField::Description, "ui/Woodwarrior-Regular.otf", 18.0),
+                                ] {
+                                    parent.spawn((
+                                        TextBundle::from_section("", text_style(font, font_size, Color::WHITE)),
+                                        UpgradeCardText { index, field },
+                                    ));
+                                }
+                                parent.spawn(TextBundle::from_section(
+                                    format!("[{}]", index + 1),
+                                    text_style("ui/Woodwarrior-Regular.otf", 18.0, Color::rgb(0.7, 0.7, 0.7)),
+                                ));
+                                parent
+                                    .spawn((
+                                        ButtonBundle {
+                                            style: Style {
+                                                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                                                margin: UiRect::top(Val::Auto),
+                                                ..default()
+                                            },
+                                            background_color: Color::rgb(0.5, 0.15, 0.15).into(),
+                                            ..default()
+                                        },
+                                        BanishButton { index },
+                                    ))
+                                    .with_children(|parent| {
+                                        parent.spawn(TextBundle::from_section(
+                                            "Banish",
+                                            text_style("ui/Woodwarrior-Regular.otf", 16.0, Color::WHITE),
+                                        ));
+                                    });
+                            });
+                    }
+                });
+            
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(20.0), Val::Px(8.0)),
+                            margin: UiRect::top(Val::Px(30.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.2, 0.4, 0.8).into(),
+                        ..default()
+                    },
+                    RerollButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Reroll",
+                        text_style("ui/Woodwarrior-Regular.otf", 20.0, Color::WHITE),
+                    ));
+                });
+            
+            parent.spawn((
+                TextBundle::from_section("", text_style("ui/Woodwarrior-Regular.otf", 18.0, Color::WHITE))
+                    .with_style(Style {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    }),
+                LevelUpChargesText,
+            ));
+        });
 }
 
-#[derive(Clone, Debug)]
-pub enum UpgradeType {
-    HealthBoost,
-    ShieldBoost,
-    DamageBoost,
-    FireRateBoost,
-    SpeedBoost,
-    NewWeapon,
+fn level_up_card_system(
+    catalogue: Res<UpgradeCatalogue>,
+    upgrade_manager: Res<UpgradeManager>,
+    mut card_query: Query<(&UpgradeButton, &mut BackgroundColor, &mut Visibility)>,
+    mut text_query: Query<(&UpgradeCardText, &mut Text), Without<LevelUpChargesText>>,
+    mut charges_text_query: Query<&mut Text, With<LevelUpChargesText>>,
+) {
+    let offers = &upgrade_manager.level_up_offers;
+    for (button, mut color, mut visibility) in card_query.iter_mut() {
+        *visibility = if button.index < offers.len() { Visibility::Inherited } else { Visibility::Hidden };
+        *color = if button.index == upgrade_manager.level_up_cursor {
+            Color::rgb(0.3, 0.5, 0.9).into()
+        } else {
+            Color::rgb(0.2, 0.4, 0.8).into()
+        };
+    }
+    
+    for (card_text, mut text) in text_query.iter_mut() {
+        let Some(upgrade) = offers.get(card_text.index) else {
+            continue;
+        };
+        let section = &mut text.sections[0];
+        match card_text.field {
+            UpgradeCardField::Name => section.value = upgrade.name.clone(),
+            UpgradeCardField::Rarity => {
+                section.value = format!("{:?} - Tier {}", upgrade.rarity, upgrade.tier);
+                section.style.color = upgrade.rarity.glow().0;
+            }
+            UpgradeCardField::Description => section.value = upgrade.description.clone(),
+        }
+    }
+    
+    if let Ok(mut text) = charges_text_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "[R] Reroll ({} left)   [B] Banish ({} left)",
+            upgrade_manager.rerolls_left(&catalogue),
+            upgrade_manager.banishes_left(&catalogue),
+        );
+    }
+}
+
+fn level_up_button_system(
+    card_query: Query<(&Interaction, &UpgradeButton), Changed<Interaction>>,
+    banish_query: Query<(&Interaction, &BanishButton), Changed<Interaction>>,
+    reroll_query: Query<&Interaction, (Changed<Interaction>, With<RerollButton>)>,
+    mut highlight_events: EventWriter<UpgradeHighlightEvent>,
+    mut chosen_events: EventWriter<UpgradeChosenEvent>,
+    mut banish_events: EventWriter<UpgradeBanishEvent>,
+    mut reroll_events: EventWriter<UpgradeRerollEvent>,
+) {
+    for (interaction, button) in card_query.iter() {
+        match *interaction {
+            Interaction::Pressed => chosen_events.send(UpgradeChosenEvent { index: button.index }),
+            Interaction::Hovered => highlight_events.send(UpgradeHighlightEvent { index: button.index }),
+            Interaction::None => {}
+        }
+    }
+    for (interaction, button) in banish_query.iter() {
+        if *interaction == Interaction::Pressed {
+            banish_events.send(UpgradeBanishEvent { index: button.index });
+        }
+    }
+    for interaction in reroll_query.iter() {
+        if *interaction == Interaction::Pressed {
+            reroll_events.send(UpgradeRerollEvent);
+        }
+    }
+}
+
+fn cleanup_level_up_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<UpgradeSelectionUI>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
 // Death screen UI
-pub fn setup_death_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn setup_death_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_rng: Res<GameRng>,
+    survival_run: Res<SurvivalRun>,
+) {
+    // Surviving the full timeline also ends the run here, but as a win
+    let (title, background) = if survival_run.victory {
+        ("VICTORY", Color::rgba(0.0, 0.5, 0.2, 0.7))
+    } else {
+        ("GAME OVER", Color::rgba(0.8, 0.0, 0.0, 0.7))
+    };
+    
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: background.into(),
                 ..default()
             },
-            background_color: Color::rgba(0.8, 0.0, 0.0, 0.7).into(),
-            ..default()
-        })
+            DeathScreenUI,
+        ))
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
-                "GAME OVER",
+                title,
                 TextStyle {
                     font: asset_server.load("ui/Woodwarrior-Bold.otf"),
                     font_size: 48.0,
                     color: Color::WHITE,
                 },
             ));
+            
+            if survival_run.victory {
+                let survived = survival_run.elapsed as u32;
+                parent.spawn(TextBundle::from_section(
+                    format!("Survived {}:{:02}", survived / 60, survived % 60),
+                    TextStyle {
+                        font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                        font_size: 28.0,
+                        color: Color::WHITE,
+                    },
+                ).with_style(Style {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                }));
+            }
+            
+            // Replay this run with --seed <seed>
+            parent.spawn(TextBundle::from_section(
+                format!("Seed: {}", game_rng.seed()),
+                TextStyle {
+                    font: asset_server.load("ui/Woodwarrior-Regular.otf"),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+            ).with_style(Style {
+                margin: UiRect::top(Val::Px(20.0)),
+                ..default()
+            }));
         });
 }
+
+fn cleanup_death_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<DeathScreenUI>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,7 +1,18 @@
 //! Upgrade system - handles player progression and power-ups
 use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use crate::GameState;
-use crate::player::{Player, WeaponType};
+use crate::config::WaveConfig;
+use crate::loot::{Rarity, RarityWeights};
+use crate::player::{Player, WeaponType, MAX_WEAPON_LEVEL};
+use crate::rng::GameRng;
+use crate::simulation::GameplaySet;
+use crate::wave::{WaveCompleteEvent, WaveManager};
+use crate::status::{StatusEffect, StatusEffectType};
+
+// Cards on the level-up screen and the break pick
+pub const LEVEL_UP_OFFERS: usize = 3;
 
 pub struct UpgradePlugin;
 
@@ -9,8 +20,21 @@ impl Plugin for UpgradePlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<UpgradeManager>()
+            .init_resource::<UpgradeCatalogue>()
+            .add_event::<UpgradeHighlightEvent>()
+            .add_event::<UpgradeChosenEvent>()
+            .add_event::<UpgradeRerollEvent>()
+            .add_event::<UpgradeBanishEvent>()
+            .add_systems(Startup, load_upgrade_catalogue)
+            // Checked once the tick's experience is all in
+            .add_systems(FixedUpdate, level_up_check_system
+                .after(crate::wave::director_update_system)
+                .in_set(GameplaySet::Progression)
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(Update, upgrade_selection_system.run_if(in_state(GameState::LevelUp)))
             .add_systems(Update, (
-                upgrade_selection_system,
+                break_upgrade_offer_system,
+                break_upgrade_pick_system,
             ).run_if(in_state(GameState::Playing)));
     }
 }
@@ -19,6 +43,34 @@ impl Plugin for UpgradePlugin {
 pub struct UpgradeManager {
     pub available_upgrades: Vec<Upgrade>,
     pub upgrade_selection_active: bool,
+    pub level_up_offers: Vec<Upgrade>, // The cards on the level-up screen
+    pub level_up_cursor: usize, // Card highlighted for keyboard and gamepad picks
+    pub taken: Vec<UpgradeType>, // Every upgrade applied this run, for max stacks and prerequisites
+    pub banished: Vec<UpgradeType>, // Never offered again this run
+    pub rerolls_used: u32,
+    pub banishes_used: u32,
+}
+
+// Move the level-up highlight to card `index`, sent by hovering a card
+#[derive(Event)]
+pub struct UpgradeHighlightEvent {
+    pub index: usize,
+}
+
+// Pick level-up card `index`, sent by clicking a card
+#[derive(Event)]
+pub struct UpgradeChosenEvent {
+    pub index: usize,
+}
+
+// Spend a reroll charge on a fresh set of level-up cards
+#[derive(Event)]
+pub struct UpgradeRerollEvent;
+
+// Spend a banish charge to drop level-up card `index` from the pool for the rest of the run
+#[derive(Event)]
+pub struct UpgradeBanishEvent {
+    pub index: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -27,20 +79,24 @@ pub struct Upgrade {
     pub name: String,
     pub description: String,
     pub tier: u32,
+    pub rarity: Rarity,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum UpgradeType {
     HealthIncrease(f32),
     ShieldIncrease(f32),
     DamageMultiplier(f32),
     FireRateIncrease(f32),
     SpeedIncrease(f32),
+    MagnetRange(f32),
+    MagnetPull(f32),
     WeaponUnlock(WeaponType),
     SpecialAbility(SpecialAbility),
+    EvolveWeapon { from: WeaponType, into: WeaponType },
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SpecialAbility {
     DoubleShot,
     PiercingShots,
@@ -49,55 +105,342 @@ pub enum SpecialAbility {
     Dash,
 }
 
+impl SpecialAbility {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpecialAbility::DoubleShot => "Double Shot",
+            SpecialAbility::PiercingShots => "Piercing Shots",
+            SpecialAbility::ExplosiveShots => "Explosive Shots",
+            SpecialAbility::ShieldRegeneration => "Shield Regeneration",
+            SpecialAbility::Dash => "Dash",
+        }
+    }
+    
+    pub fn description(&self) -> &'static str {
+        match self {
+            SpecialAbility::DoubleShot => "Every shot fires an extra copy alongside it",
+            SpecialAbility::PiercingShots => "Shots pass through more enemies",
+            SpecialAbility::ExplosiveShots => "Shots explode and burn on hit",
+            SpecialAbility::ShieldRegeneration => "Shields recharge faster and sooner after a hit",
+            SpecialAbility::Dash => "Hold Shift to dash through danger, costs special energy",
+        }
+    }
+}
+
+// Total bonus after `stacks` picks: the first is worth `first`, each one after `falloff` times the last
+pub fn diminishing(stacks: u32, first: f32, falloff: f32) -> f32 {
+    (0..stacks).map(|stack| first * falloff.powi(stack as i32)).sum()
+}
+
+pub const DASH_SPEED: f32 = 900.0;
+pub const DASH_DURATION: f32 = 0.2; // Invulnerable for the whole dash
+const DASH_ENERGY_COST: f32 = 50.0;
+const DOUBLE_SHOT_SPACING: f32 = 12.0; // Gap between side-by-side copies of a shot
+
+// How many times the player has taken each special ability
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpecialAbilities {
+    pub double_shot: u32,
+    pub piercing_shots: u32,
+    pub explosive_shots: u32,
+    pub shield_regeneration: u32,
+    pub dash: u32,
+}
+
+impl SpecialAbilities {
+    pub fn stacks(&self, ability: &SpecialAbility) -> u32 {
+        match ability {
+            SpecialAbility::DoubleShot => self.double_shot,
+            SpecialAbility::PiercingShots => self.piercing_shots,
+            SpecialAbility::ExplosiveShots => self.explosive_shots,
+            SpecialAbility::ShieldRegeneration => self.shield_regeneration,
+            SpecialAbility::Dash => self.dash,
+        }
+    }
+    
+    /// Takes another stack of `ability`, returning the new count
+    pub fn add(&mut self, ability: &SpecialAbility) -> u32 {
+        let stacks = match ability {
+            SpecialAbility::DoubleShot => &mut self.double_shot,
+            SpecialAbility::PiercingShots => &mut self.piercing_shots,
+            SpecialAbility::ExplosiveShots => &mut self.explosive_shots,
+            SpecialAbility::ShieldRegeneration => &mut self.shield_regeneration,
+            SpecialAbility::Dash => &mut self.dash,
+        };
+        *stacks += 1;
+        *stacks
+    }
+    
+    /// Sideways offsets of each copy of a shot, one copy per Double Shot stack
+    pub fn shot_offsets(&self) -> Vec<f32> {
+        let half = self.double_shot as f32 * 0.5;
+        (0..=self.double_shot).map(|i| (i as f32 - half) * DOUBLE_SHOT_SPACING).collect()
+    }
+    
+    /// Damage each copy deals. The first extra copy is a full shot, later ones add less and less.
+    pub fn shot_damage_scale(&self) -> f32 {
+        (1.0 + diminishing(self.double_shot, 1.0, 0.5)) / (1 + self.double_shot) as f32
+    }
+    
+    pub fn extra_pierce(&self) -> u32 {
+        diminishing(self.piercing_shots, 2.0, 0.75).floor() as u32
+    }
+    
+    // Added on top of any splash the weapon already has
+    pub fn splash_radius(&self) -> f32 {
+        diminishing(self.explosive_shots, 50.0, 0.6)
+    }
+    
+    // Speeds up shield regen and shortens the wait before it starts by the same factor
+    pub fn shield_regen_multiplier(&self) -> f32 {
+        1.0 + diminishing(self.shield_regeneration, 0.5, 0.6)
+    }
+    
+    /// Special energy a dash costs, or None before Dash is unlocked. Stacks past the first make it cheaper.
+    pub fn dash_cost(&self) -> Option<f32> {
+        (self.dash > 0).then(|| DASH_ENERGY_COST / (1.0 + diminishing(self.dash - 1, 0.5, 0.6)))
+    }
+}
+
+impl Upgrade {
+    pub fn new(upgrade_type: UpgradeType, tier: u32) -> Self {
+        Self {
+            name: get_upgrade_name(&upgrade_type),
+            description: get_upgrade_description(&upgrade_type),
+            upgrade_type,
+            tier,
+            rarity: Rarity::Common,
+        }
+    }
+}
+
+// Everything level-ups, break picks and the shop can offer, loaded from config/upgrades.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct UpgradeCatalogue {
+    pub rarity_weights: RarityWeights,
+    // Each level past the first multiplies the better tiers' weights by this much more
+    // (Rare once, Epic twice, Legendary three times)
+    pub rarity_scaling_per_level: f32,
+    pub reroll_charges: u32, // Per run
+    pub banish_charges: u32,
+    pub upgrades: Vec<UpgradeEntry>,
+    #[serde(default)]
+    pub evolutions: Vec<EvolutionRecipe>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpgradeEntry {
+    pub id: String, // What `requires` and `excludes` refer to
+    pub upgrade_type: UpgradeType,
+    pub weight: f32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub max_stacks: Option<u32>, // Unlimited when left out
+    #[serde(default)]
+    pub requires: Vec<String>, // Must all have been taken first
+    #[serde(default)]
+    pub excludes: Vec<String>, // Taking any of these rules this one out, and the other way round
+}
+
+// A max-level `weapon` plus the `passive` upgrade (a catalogue id) evolves into `evolves_into`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EvolutionRecipe {
+    pub weapon: WeaponType,
+    pub passive: String,
+    pub evolves_into: WeaponType,
+}
+
+impl Default for UpgradeCatalogue {
+    fn default() -> Self {
+        let entry = |id: &str, upgrade_type: UpgradeType, weight: f32, rarity: Rarity, max_stacks: Option<u32>| UpgradeEntry {
+            id: id.to_string(),
+            upgrade_type,
+            weight,
+            rarity,
+            max_stacks,
+            requires: Vec::new(),
+            excludes: Vec::new(),
+        };
+        let weapon = |id: &str, weapon: WeaponType| entry(id, UpgradeType::WeaponUnlock(weapon), 0.6, Rarity::Rare, None);
+        let ability = |id: &str, ability: SpecialAbility, weight: f32, rarity: Rarity, max_stacks: u32| {
+            entry(id, UpgradeType::SpecialAbility(ability), weight, rarity, Some(max_stacks))
+        };
+        
+        Self {
+            rarity_weights: RarityWeights {
+                common: 80.0,
+                rare: 30.0,
+                epic: 10.0,
+                legendary: 3.0,
+            },
+            rarity_scaling_per_level: 0.1,
+            reroll_charges: 2,
+            banish_charges: 2,
+            upgrades: vec![
+                entry("health", UpgradeType::HealthIncrease(25.0), 1.0, Rarity::Common, None),
+                entry("shields", UpgradeType::ShieldIncrease(15.0), 1.0, Rarity::Common, None),
+                entry("damage", UpgradeType::DamageMultiplier(0.2), 1.0, Rarity::Common, Some(10)),
+                entry("fire_rate", UpgradeType::FireRateIncrease(1.0), 1.0, Rarity::Common, Some(8)),
+                entry("speed", UpgradeType::SpeedIncrease(50.0), 0.8, Rarity::Common, Some(5)),
+                entry("magnet_range", UpgradeType::MagnetRange(40.0), 0.6, Rarity::Common, Some(5)),
+                entry("magnet_pull", UpgradeType::MagnetPull(100.0), 0.6, Rarity::Common, Some(5)),
+                weapon("blaster", WeaponType::Blaster),
+                weapon("laser", WeaponType::Laser),
+                weapon("rocket", WeaponType::Rocket),
+                weapon("pulse", WeaponType::AoePulse),
+                ability("double_shot", SpecialAbility::DoubleShot, 0.5, Rarity::Epic, 3),
+                ability("piercing_shots", SpecialAbility::PiercingShots, 0.6, Rarity::Rare, 4),
+                ability("explosive_shots", SpecialAbility::ExplosiveShots, 0.6, Rarity::Rare, 4),
+                UpgradeEntry {
+                    requires: vec!["shields".to_string()],
+                    ..ability("shield_regeneration", SpecialAbility::ShieldRegeneration, 0.6, Rarity::Rare, 4)
+                },
+                ability("dash", SpecialAbility::Dash, 0.4, Rarity::Epic, 3),
+            ],
+            evolutions: vec![
+                EvolutionRecipe { weapon: WeaponType::Laser, passive: "piercing_shots".to_string(), evolves_into: WeaponType::PrismBeam },
+                EvolutionRecipe { weapon: WeaponType::Rocket, passive: "explosive_shots".to_string(), evolves_into: WeaponType::ClusterMissiles },
+            ],
+        }
+    }
+}
+
+impl UpgradeCatalogue {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let catalogue_str = std::fs::read_to_string("config/upgrades.ron")?;
+        let catalogue: UpgradeCatalogue = ron::de::from_str(&catalogue_str)?;
+        Ok(catalogue)
+    }
+    
+    pub fn entry(&self, id: &str) -> Option<&UpgradeEntry> {
+        self.upgrades.iter().find(|entry| entry.id == id)
+    }
+    
+    /// How much more likely an upgrade of `rarity` gets offered at `level`, the better tiers
+    /// climbing with level the way loot rarity climbs with waves
+    pub fn rarity_weight(&self, rarity: Rarity, level: u32) -> f32 {
+        let scaling = 1.0 + level.saturating_sub(1) as f32 * self.rarity_scaling_per_level;
+        let weights = &self.rarity_weights;
+        let weight = match rarity {
+            Rarity::Common => weights.common,
+            Rarity::Rare => weights.rare * scaling,
+            Rarity::Epic => weights.epic * scaling.powi(2),
+            Rarity::Legendary => weights.legendary * scaling.powi(3),
+        };
+        weight.max(0.0)
+    }
+}
+
 impl UpgradeManager {
-    pub fn generate_random_upgrades(&mut self, player_level: u32) -> Vec<Upgrade> {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let mut upgrades = Vec::new();
+    /// Up to `count` different upgrades the player can take right now, leaving out `exclude`.
+    /// Any weapon evolutions that are ready come first, the rest are weighted by each entry's
+    /// weight and rarity and drawn without replacement.
+    pub fn generate_random_upgrades(
+        &self,
+        catalogue: &UpgradeCatalogue,
+        player: &Player,
+        count: usize,
+        exclude: &[UpgradeType],
+        rng: &mut impl Rng,
+    ) -> Vec<Upgrade> {
+        let tier = (player.level / 5).max(1);
+        let mut upgrades: Vec<Upgrade> = self.ready_evolutions(catalogue, player)
+            .into_iter()
+            .map(|recipe| UpgradeType::EvolveWeapon { from: recipe.weapon.clone(), into: recipe.evolves_into.clone() })
+            .filter(|upgrade_type| !exclude.contains(upgrade_type))
+            .take(count)
+            .map(|upgrade_type| Upgrade { rarity: Rarity::Legendary, ..Upgrade::new(upgrade_type, tier) })
+            .collect();
         
-        // Generate 3 random upgrades
-        for _ in 0..3 {
-            let upgrade_type = match rng.gen_range(0..7) {
-                0 => UpgradeType::HealthIncrease(25.0),
-                1 => UpgradeType::ShieldIncrease(15.0),
-                2 => UpgradeType::DamageMultiplier(0.2),
-                3 => UpgradeType::FireRateIncrease(1.0),
-                4 => UpgradeType::SpeedIncrease(50.0),
-                5 => {
-                    let weapon = match rng.gen_range(0..4) {
-                        0 => WeaponType::Blaster,
-                        1 => WeaponType::Laser,
-                        2 => WeaponType::Rocket,
-                        _ => WeaponType::AoePulse,
-                    };
-                    UpgradeType::WeaponUnlock(weapon)
-                }
-                _ => {
-                    let ability = match rng.gen_range(0..5) {
-                        0 => SpecialAbility::DoubleShot,
-                        1 => SpecialAbility::PiercingShots,
-                        2 => SpecialAbility::ExplosiveShots,
-                        3 => SpecialAbility::ShieldRegeneration,
-                        _ => SpecialAbility::Dash,
-                    };
-                    UpgradeType::SpecialAbility(ability)
-                }
-            };
-            
-            let upgrade = Upgrade {
-                upgrade_type: upgrade_type.clone(),
-                name: get_upgrade_name(&upgrade_type),
-                description: get_upgrade_description(&upgrade_type),
-                tier: (player_level / 5).max(1),
-            };
+        let mut pool: Vec<(&UpgradeEntry, f32)> = catalogue.upgrades
+            .iter()
+            .filter(|entry| !exclude.contains(&entry.upgrade_type) && self.is_available(catalogue, entry, player))
+            .map(|entry| (entry, entry.weight * catalogue.rarity_weight(entry.rarity, player.level)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        
+        while upgrades.len() < count && !pool.is_empty() {
+            let total: f32 = pool.iter().map(|(_, weight)| weight).sum();
+            let mut roll = rng.gen_range(0.0..total);
+            let index = pool
+                .iter()
+                .position(|(_, weight)| {
+                    if roll < *weight {
+                        return true;
+                    }
+                    roll -= weight;
+                    false
+                })
+                .unwrap_or(pool.len() - 1);
             
-            upgrades.push(upgrade);
+            let (entry, _) = pool.remove(index);
+            upgrades.push(Upgrade {
+                rarity: entry.rarity,
+                ..Upgrade::new(entry.upgrade_type.clone(), tier)
+            });
         }
         
         upgrades
     }
     
-    pub fn apply_upgrade(&self, upgrade: &Upgrade, player: &mut Player) {
+    /// Recipes the player can evolve right now: the weapon is at max level, the passive is taken
+    /// and the evolution hasn't been banished
+    pub fn ready_evolutions<'a>(&self, catalogue: &'a UpgradeCatalogue, player: &Player) -> Vec<&'a EvolutionRecipe> {
+        catalogue.evolutions
+            .iter()
+            .filter(|recipe| {
+                let evolution = UpgradeType::EvolveWeapon { from: recipe.weapon.clone(), into: recipe.evolves_into.clone() };
+                if self.banished.contains(&evolution) {
+                    return false;
+                }
+                let weapon_maxed = player.arsenal
+                    .iter()
+                    .any(|weapon| weapon.weapon_type == recipe.weapon && weapon.level >= MAX_WEAPON_LEVEL);
+                let passive_taken = catalogue.entry(&recipe.passive)
+                    .is_some_and(|entry| self.times_taken(&entry.upgrade_type) > 0);
+                weapon_maxed && passive_taken
+            })
+            .collect()
+    }
+    
+    pub fn times_taken(&self, upgrade_type: &UpgradeType) -> u32 {
+        self.taken.iter().filter(|taken| *taken == upgrade_type).count() as u32
+    }
+    
+    /// Whether `entry` can be offered: not banished or maxed out, prerequisites taken, nothing
+    /// excluding it taken, and for weapons not already at max level
+    pub fn is_available(&self, catalogue: &UpgradeCatalogue, entry: &UpgradeEntry, player: &Player) -> bool {
+        if self.banished.contains(&entry.upgrade_type) {
+            return false;
+        }
+        if entry.max_stacks.is_some_and(|max_stacks| self.times_taken(&entry.upgrade_type) >= max_stacks) {
+            return false;
+        }
+        if let UpgradeType::WeaponUnlock(weapon) = &entry.upgrade_type {
+            if !player.weapon_crate_options().contains(weapon) {
+                return false;
+            }
+        }
+        
+        let has_taken = |id: &String| catalogue.entry(id).is_some_and(|other| self.times_taken(&other.upgrade_type) > 0);
+        let excluded_by_taken = catalogue.upgrades
+            .iter()
+            .any(|other| other.excludes.contains(&entry.id) && self.times_taken(&other.upgrade_type) > 0);
+        
+        entry.requires.iter().all(has_taken) && !entry.excludes.iter().any(has_taken) && !excluded_by_taken
+    }
+    
+    pub fn rerolls_left(&self, catalogue: &UpgradeCatalogue) -> u32 {
+        catalogue.reroll_charges.saturating_sub(self.rerolls_used)
+    }
+    
+    pub fn banishes_left(&self, catalogue: &UpgradeCatalogue) -> u32 {
+        catalogue.banish_charges.saturating_sub(self.banishes_used)
+    }
+    
+    pub fn apply_upgrade(&mut self, upgrade: &Upgrade, player: &mut Player) {
+        self.taken.push(upgrade.upgrade_type.clone());
         match &upgrade.upgrade_type {
             UpgradeType::HealthIncrease(amount) => {
                 player.max_health += amount;
@@ -116,12 +459,28 @@ impl UpgradeManager {
             UpgradeType::SpeedIncrease(increase) => {
                 player.speed += increase;
             }
+            UpgradeType::MagnetRange(increase) => {
+                player.magnet_range += increase;
+            }
+            UpgradeType::MagnetPull(increase) => {
+                player.magnet_pull += increase;
+            }
             UpgradeType::WeaponUnlock(weapon) => {
-                player.primary_weapon = weapon.clone();
+                // Already owned weapons level up instead
+                player.add_weapon(weapon.clone());
             }
-            UpgradeType::SpecialAbility(_ability) => {
-                // TODO: Implement special abilities
-                info!("Special ability unlocked: {:?}", _ability);
+            UpgradeType::EvolveWeapon { from, into } => {
+                if player.evolve_weapon(from, into.clone()) {
+                    info!("{} evolved into {}", from.name(), into.name());
+                }
+            }
+            UpgradeType::SpecialAbility(ability) => {
+                let stacks = player.abilities.add(ability);
+                // Explosive rounds also set targets on fire
+                if *ability == SpecialAbility::ExplosiveShots && stacks == 1 {
+                    player.on_hit_effects.push(StatusEffect::new(StatusEffectType::Burn));
+                }
+                info!("{} now at {} stacks", ability.name(), stacks);
             }
         }
         
@@ -136,8 +495,11 @@ fn get_upgrade_name(upgrade_type: &UpgradeType) -> String {
         UpgradeType::DamageMultiplier(_) => "Damage Boost".to_string(),
         UpgradeType::FireRateIncrease(_) => "Fire Rate Boost".to_string(),
         UpgradeType::SpeedIncrease(_) => "Speed Boost".to_string(),
+        UpgradeType::MagnetRange(_) => "Magnet Range".to_string(),
+        UpgradeType::MagnetPull(_) => "Magnet Pull".to_string(),
         UpgradeType::WeaponUnlock(weapon) => format!("{:?} Weapon", weapon),
-        UpgradeType::SpecialAbility(ability) => format!("{:?}", ability),
+        UpgradeType::SpecialAbility(ability) => ability.name().to_string(),
+        UpgradeType::EvolveWeapon { into, .. } => into.name().to_string(),
     }
 }
 
@@ -148,13 +510,199 @@ fn get_upgrade_description(upgrade_type: &UpgradeType) -> String {
         UpgradeType::DamageMultiplier(multiplier) => format!("Increase damage by {}%", (multiplier * 100.0) as u32),
         UpgradeType::FireRateIncrease(increase) => format!("Increase fire rate by {}", increase),
         UpgradeType::SpeedIncrease(increase) => format!("Increase movement speed by {}", increase),
+        UpgradeType::MagnetRange(increase) => format!("Pull in loot from {} further away", increase),
+        UpgradeType::MagnetPull(increase) => format!("Pull in loot {} faster", increase),
         UpgradeType::WeaponUnlock(weapon) => format!("Unlock the {:?} weapon", weapon),
-        UpgradeType::SpecialAbility(ability) => format!("Unlock special ability: {:?}", ability),
+        UpgradeType::SpecialAbility(ability) => ability.description().to_string(),
+        UpgradeType::EvolveWeapon { from, into } => format!("Evolve the {} into the {}. {}", from.name(), into.name(), into.description()),
+    }
+}
+
+// Offer a free upgrade pick at the start of every break
+fn break_upgrade_offer_system(
+    config: Res<WaveConfig>,
+    catalogue: Res<UpgradeCatalogue>,
+    mut game_rng: ResMut<GameRng>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
+    mut wave_events: EventReader<WaveCompleteEvent>,
+    player_query: Query<&Player>,
+) {
+    for _ in wave_events.read() {
+        if !config.break_upgrade_pick {
+            continue;
+        }
+        
+        if let Ok(player) = player_query.get_single() {
+            upgrade_manager.available_upgrades = upgrade_manager.generate_random_upgrades(&catalogue, player, LEVEL_UP_OFFERS, &[], game_rng.upgrades());
+            upgrade_manager.upgrade_selection_active = !upgrade_manager.available_upgrades.is_empty();
+        }
+    }
+}
+
+fn break_upgrade_pick_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    wave_manager: Res<WaveManager>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
+    mut player_query: Query<&mut Player>,
+) {
+    if !upgrade_manager.upgrade_selection_active {
+        return;
+    }
+    
+    // Offers that weren't taken expire when the next wave starts
+    if wave_manager.wave_active {
+        upgrade_manager.available_upgrades.clear();
+        upgrade_manager.upgrade_selection_active = false;
+        return;
+    }
+    
+    let choice = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3]
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key));
+    
+    if let Some(index) = choice {
+        if let (Some(upgrade), Ok(mut player)) = (
+            upgrade_manager.available_upgrades.get(index).cloned(),
+            player_query.get_single_mut(),
+        ) {
+            upgrade_manager.apply_upgrade(&upgrade, &mut player);
+            upgrade_manager.available_upgrades.clear();
+            upgrade_manager.upgrade_selection_active = false;
+        }
     }
 }
 
-fn upgrade_selection_system(
-    // TODO: Implement upgrade selection UI interaction
+// Holds the game on the level-up screen while the player has level-ups to spend
+pub fn level_up_check_system(
+    catalogue: Res<UpgradeCatalogue>,
+    mut game_rng: ResMut<GameRng>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<&mut Player>,
 ) {
-    // This will be implemented when the upgrade UI is complete
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+    if player.pending_level_ups == 0 || !upgrade_manager.level_up_offers.is_empty() {
+        return;
+    }
+    
+    let offers = upgrade_manager.generate_random_upgrades(&catalogue, &player, LEVEL_UP_OFFERS, &[], game_rng.upgrades());
+    if offers.is_empty() {
+        // Everything is maxed out or banished, so there's nothing to pick
+        player.pending_level_ups -= 1;
+        return;
+    }
+    
+    upgrade_manager.level_up_offers = offers;
+    upgrade_manager.level_up_cursor = 0;
+    next_state.set(GameState::LevelUp);
+    info!("Level up! Choose an upgrade");
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn upgrade_selection_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    catalogue: Res<UpgradeCatalogue>,
+    mut game_rng: ResMut<GameRng>,
+    mut upgrade_manager: ResMut<UpgradeManager>,
+    mut highlight_events: EventReader<UpgradeHighlightEvent>,
+    mut chosen_events: EventReader<UpgradeChosenEvent>,
+    mut reroll_events: EventReader<UpgradeRerollEvent>,
+    mut banish_events: EventReader<UpgradeBanishEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<&mut Player>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+    if upgrade_manager.level_up_offers.is_empty() {
+        return;
+    }
+    let gamepad_pressed = |button_type: GamepadButtonType| {
+        gamepad_input.get_just_pressed().any(|button| button.button_type == button_type)
+    };
+    
+    // Hovering moves the highlight so mouse and keyboard share one cursor
+    if let Some(event) = highlight_events.read().last() {
+        upgrade_manager.level_up_cursor = event.index;
+    }
+    
+    // Banishing swaps the card for one that isn't on screen yet
+    let banish = banish_events.read().last().map(|event| event.index).or_else(|| {
+        let banish = keyboard_input.just_pressed(KeyCode::B) || gamepad_pressed(GamepadButtonType::North);
+        banish.then_some(upgrade_manager.level_up_cursor)
+    });
+    if let Some(index) = banish.filter(|index| *index < upgrade_manager.level_up_offers.len()) {
+        if upgrade_manager.banishes_left(&catalogue) > 0 {
+            upgrade_manager.banishes_used += 1;
+            let banished = upgrade_manager.level_up_offers.remove(index);
+            info!("Banished {}", banished.name);
+            upgrade_manager.banished.push(banished.upgrade_type);
+            
+            let on_screen: Vec<UpgradeType> = upgrade_manager.level_up_offers.iter().map(|upgrade| upgrade.upgrade_type.clone()).collect();
+            let replacement = upgrade_manager.generate_random_upgrades(&catalogue, &player, 1, &on_screen, game_rng.upgrades());
+            for (offset, upgrade) in replacement.into_iter().enumerate() {
+                upgrade_manager.level_up_offers.insert(index + offset, upgrade);
+            }
+        }
+    }
+    
+    let reroll = reroll_events.read().count() > 0
+        || keyboard_input.just_pressed(KeyCode::R)
+        || gamepad_pressed(GamepadButtonType::West);
+    if reroll && upgrade_manager.rerolls_left(&catalogue) > 0 {
+        upgrade_manager.rerolls_used += 1;
+        upgrade_manager.level_up_offers = upgrade_manager.generate_random_upgrades(&catalogue, &player, LEVEL_UP_OFFERS, &[], game_rng.upgrades());
+    }
+    
+    // Banishing the last card there was leaves nothing to pick
+    let offers = upgrade_manager.level_up_offers.len();
+    if offers == 0 {
+        player.pending_level_ups = player.pending_level_ups.saturating_sub(1);
+        next_state.set(GameState::Playing);
+        return;
+    }
+    upgrade_manager.level_up_cursor = upgrade_manager.level_up_cursor.min(offers - 1);
+    
+    // Move the highlight
+    let left = keyboard_input.any_just_pressed([KeyCode::Left, KeyCode::A]) || gamepad_pressed(GamepadButtonType::DPadLeft);
+    let right = keyboard_input.any_just_pressed([KeyCode::Right, KeyCode::D]) || gamepad_pressed(GamepadButtonType::DPadRight);
+    if left {
+        upgrade_manager.level_up_cursor = (upgrade_manager.level_up_cursor + offers - 1) % offers;
+    }
+    if right {
+        upgrade_manager.level_up_cursor = (upgrade_manager.level_up_cursor + 1) % offers;
+    }
+    
+    // Number keys pick a card directly, Enter or the gamepad's south button takes the highlighted one
+    let choice = chosen_events.read().last().map(|event| event.index)
+        .or_else(|| [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3]
+            .iter()
+            .position(|key| keyboard_input.just_pressed(*key)))
+        .or_else(|| {
+            let confirm = keyboard_input.just_pressed(KeyCode::Return) || gamepad_pressed(GamepadButtonType::South);
+            confirm.then_some(upgrade_manager.level_up_cursor)
+        });
+    
+    let Some(upgrade) = choice.and_then(|index| upgrade_manager.level_up_offers.get(index).cloned()) else {
+        return;
+    };
+    upgrade_manager.apply_upgrade(&upgrade, &mut player);
+    player.pending_level_ups = player.pending_level_ups.saturating_sub(1);
+    upgrade_manager.level_up_offers.clear();
+    next_state.set(GameState::Playing);
+}
+
+fn load_upgrade_catalogue(mut catalogue: ResMut<UpgradeCatalogue>) {
+    match UpgradeCatalogue::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded {} upgrades", loaded.upgrades.len());
+            *catalogue = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/upgrades.ron ({}), using the default upgrades", err);
+        }
+    }
 }
@@ -1,7 +1,15 @@
 //! Wave system - manages enemy spawning, difficulty scaling, and wave progression
 use bevy::prelude::*;
-use crate::{GameState, GameEntity};
-use crate::enemy::{spawn_enemy, EnemyType};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::{GameData, GameMode, GameState, GameEntity};
+use crate::asteroid::Asteroid;
+use crate::config::WaveConfig;
+use crate::enemy::{spawn_enemy_with_modifiers, EnemyKilledEvent, EnemyModifiers, EnemyType};
+use crate::loot::{spawn_loot_chest, LootAssets};
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::simulation::GameplaySet;
 
 pub struct WavePlugin;
 
@@ -9,12 +17,316 @@ impl Plugin for WavePlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<WaveManager>()
-            .add_systems(OnEnter(GameState::Playing), initialize_wave_system)
-            .add_systems(Update, (
-                wave_spawn_system,
+            .init_resource::<WaveStats>()
+            .init_resource::<WaveScript>()
+            .init_resource::<AIDirector>()
+            .add_event::<WaveCompleteEvent>()
+            .add_event::<BossWaveEvent>()
+            .add_systems(Startup, (load_wave_script, setup_telegraph_assets))
+            .add_systems(OnEnter(GameState::Playing), (
+                initialize_wave_system.run_if(resource_equals(GameMode::Waves)),
+                initialize_director_system,
+            ))
+            .add_systems(FixedUpdate, (
                 wave_progression_system,
+                wave_spawn_system,
+            ).chain()
+             .before(spawn_telegraph_system)
+             .in_set(GameplaySet::Spawning)
+             .run_if(in_state(GameState::Playing))
+             .run_if(resource_equals(GameMode::Waves)))
+            .add_systems(FixedUpdate, (
                 check_wave_completion_system,
-            ).run_if(in_state(GameState::Playing)));
+                handle_wave_complete,
+                handle_boss_wave,
+            ).chain()
+             .before(record_kill_stats_system)
+             .in_set(GameplaySet::Progression)
+             .run_if(in_state(GameState::Playing))
+             .run_if(resource_equals(GameMode::Waves)))
+            // Key presses are per frame, so ready-up is read outside the tick
+            .add_systems(Update, break_ready_system
+                .run_if(in_state(GameState::Playing))
+                .run_if(resource_equals(GameMode::Waves)))
+            // Shared with survival mode
+            .add_systems(FixedUpdate, spawn_telegraph_system
+                .in_set(GameplaySet::Spawning)
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, (
+                record_kill_stats_system,
+                director_update_system,
+            ).chain()
+             .in_set(GameplaySet::Progression)
+             .run_if(in_state(GameState::Playing)));
+    }
+}
+
+// Authored wave definitions, loaded from config/waves.ron
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WaveScript {
+    pub waves: Vec<WaveDefinition>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WaveDefinition {
+    pub spawn_groups: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub bosses: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub modifiers: EnemyModifiers,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpawnGroup {
+    pub enemy_type: EnemyType,
+    #[serde(default = "default_group_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub formation: Formation,
+    #[serde(default)]
+    pub delay: f32, // Seconds after the wave starts
+    #[serde(default)]
+    pub edge: SpawnEdge,
+    #[serde(default)]
+    pub modifiers: EnemyModifiers,
+}
+
+fn default_group_count() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Formation {
+    #[default]
+    Single,
+    Line,
+    V,
+    Cluster,
+    Ring, // Surrounds the player instead of coming from an edge
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SpawnEdge {
+    #[default]
+    Random,
+    North,
+    South,
+    East,
+    West,
+}
+
+impl WaveScript {
+    pub fn load_from_file() -> Result<Self, Box<dyn std::error::Error>> {
+        let script_str = std::fs::read_to_string("config/waves.ron")?;
+        let script: WaveScript = ron::de::from_str(&script_str)?;
+        Ok(script)
+    }
+    
+    /// Returns the authored definition for `wave`, or a generated one once the script runs out
+    pub fn wave(&self, wave: u32, config: &WaveConfig, rng: &mut impl Rng) -> WaveDefinition {
+        match self.waves.get(wave.saturating_sub(1) as usize) {
+            Some(definition) => definition.clone(),
+            None => generate_procedural_wave(wave, config, rng),
+        }
+    }
+}
+
+const RING_RADIUS: f32 = 200.0;
+
+// Ground marker shown where an enemy is about to appear
+#[derive(Component)]
+pub struct SpawnTelegraph {
+    pub enemy_type: EnemyType,
+    pub modifiers: EnemyModifiers,
+    pub remaining: f32,
+    pub duration: f32,
+}
+
+#[derive(Resource)]
+pub struct TelegraphAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl Formation {
+    // Offsets in the X-Z plane, laid out facing into the arena along +Z
+    pub fn offsets(&self, count: u32) -> Vec<Vec3> {
+        let spacing = 30.0;
+        let half = (count as f32 - 1.0) * 0.5;
+        
+        (0..count).map(|i| {
+            let i = i as f32;
+            match self {
+                Formation::Single => Vec3::ZERO,
+                Formation::Line => Vec3::new((i - half) * spacing, 0.0, 0.0),
+                Formation::V => Vec3::new((i - half) * spacing, 0.0, -(i - half).abs() * spacing),
+                Formation::Cluster => {
+                    let angle = i * 2.4; // Golden angle spiral keeps the pack tight
+                    let radius = spacing * 0.5 * i.sqrt();
+                    Vec3::new(angle.cos() * radius, 0.0, angle.sin() * radius)
+                }
+                Formation::Ring => {
+                    let angle = i / count as f32 * std::f32::consts::TAU;
+                    Vec3::new(angle.cos() * RING_RADIUS, 0.0, angle.sin() * RING_RADIUS)
+                }
+            }
+        }).collect()
+    }
+}
+
+// Fallback generator for endless play once the authored waves run out
+pub fn generate_procedural_wave(wave: u32, config: &WaveConfig, rng: &mut impl Rng) -> WaveDefinition {
+    let total = config.base_enemies_per_wave + wave * config.enemies_scaling_per_wave;
+    let interval = (config.spawn_interval_base - wave as f32 * config.spawn_interval_reduction).max(0.5);
+    
+    let mut spawn_groups = Vec::new();
+    let mut remaining = total;
+    let mut delay = 0.0;
+    
+    while remaining > 0 {
+        let roll = rng.gen_range(0.0..1.0);
+        let enemy_type = if roll < 0.15 {
+            EnemyType::Chaser
+        } else if roll < 0.3 {
+            EnemyType::Swarm
+        } else if roll < 0.5 {
+            EnemyType::Shooter
+        } else if roll < 0.7 {
+            EnemyType::Tank
+        } else {
+            EnemyType::Elite
+        };
+        
+        let count = rng.gen_range(1..=4).min(remaining);
+        let formation = if count == 1 {
+            Formation::Single
+        } else {
+            match rng.gen_range(0..4) {
+                0 => Formation::Line,
+                1 => Formation::V,
+                2 => Formation::Ring,
+                _ => Formation::Cluster,
+            }
+        };
+        
+        spawn_groups.push(SpawnGroup {
+            enemy_type,
+            count,
+            formation,
+            delay,
+            edge: SpawnEdge::Random,
+            modifiers: EnemyModifiers::default(),
+        });
+        
+        remaining -= count;
+        delay += interval * count as f32;
+    }
+    
+    // Boss waves every 10 waves
+    let bosses = if wave.is_multiple_of(10) {
+        vec![SpawnGroup {
+            enemy_type: EnemyType::Boss,
+            count: 1,
+            formation: Formation::Single,
+            delay: delay * 0.5,
+            edge: SpawnEdge::Random,
+            modifiers: EnemyModifiers::default(),
+        }]
+    } else {
+        Vec::new()
+    };
+    
+    // Nothing authored past here, so the per-wave difficulty is what keeps endless play climbing
+    let difficulty = config.difficulty_multiplier(wave);
+    
+    WaveDefinition {
+        spawn_groups,
+        bosses,
+        modifiers: EnemyModifiers {
+            health_multiplier: difficulty,
+            damage_multiplier: difficulty,
+            ..default()
+        },
+    }
+}
+
+// Adaptive director - watches how the player is doing and scales spawn pressure
+// within the bounds set in WaveConfig. Intensity 1.0 means the script plays as written.
+#[derive(Resource)]
+pub struct AIDirector {
+    pub enabled: bool,
+    pub intensity: f32,
+    pub kill_rate: f32,         // Kills per second, smoothed
+    pub damage_taken_rate: f32, // Fraction of max health lost per second, smoothed
+    pub health_fraction: f32,
+    pub last_clear_time: Option<f32>,
+    pub last_expected_clear_time: f32,
+    last_total_health: Option<f32>,
+}
+
+impl Default for AIDirector {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.0,
+            kill_rate: 0.0,
+            damage_taken_rate: 0.0,
+            health_fraction: 1.0,
+            last_clear_time: None,
+            last_expected_clear_time: 0.0,
+            last_total_health: None,
+        }
+    }
+}
+
+impl AIDirector {
+    /// Intensity the director is steering towards given current performance
+    pub fn target_intensity(&self, config: &WaveConfig) -> f32 {
+        let mut target = 1.0;
+        
+        // Healthy players get pushed harder, struggling ones get room to breathe
+        target += (self.health_fraction - 0.5) * 0.6;
+        target += (self.kill_rate - 1.0).clamp(-1.0, 1.0) * 0.2; // Around one kill a second is par
+        target -= (self.damage_taken_rate * 20.0).min(1.0) * 0.5;
+        
+        // Clearing faster than the script expects nudges things up
+        if let Some(clear_time) = self.last_clear_time {
+            if self.last_expected_clear_time > 0.0 {
+                let ratio = self.last_expected_clear_time / clear_time.max(1.0);
+                target += (ratio - 1.0).clamp(-0.5, 0.5) * 0.3;
+            }
+        }
+        
+        target.clamp(config.director_min_intensity, config.director_max_intensity)
+    }
+    
+    /// Scales a spawn group's size by the current intensity
+    pub fn group_size(&self, count: u32) -> u32 {
+        if !self.enabled {
+            return count;
+        }
+        ((count as f32 * self.intensity).round() as u32).max(1)
+    }
+    
+    /// Shifts an enemy one tier up or down based on how far intensity is from 1.0
+    pub fn adjust_enemy_type(&self, enemy_type: EnemyType, rng: &mut impl Rng) -> EnemyType {
+        if !self.enabled || enemy_type == EnemyType::Boss {
+            return enemy_type;
+        }
+        
+        let shift_chance = ((self.intensity - 1.0).abs() * 0.5) as f64;
+        if !rng.gen_bool(shift_chance.min(1.0)) {
+            return enemy_type;
+        }
+        
+        let tiers = [EnemyType::Swarm, EnemyType::Chaser, EnemyType::Shooter, EnemyType::Tank, EnemyType::Elite];
+        let index = tiers.iter().position(|tier| *tier == enemy_type).unwrap_or(0);
+        let index = if self.intensity > 1.0 {
+            (index + 1).min(tiers.len() - 1)
+        } else {
+            index.saturating_sub(1)
+        };
+        tiers[index].clone()
     }
 }
 
@@ -23,8 +335,15 @@ pub struct WaveManager {
     pub current_wave: u32,
     pub enemies_spawned: u32,
     pub enemies_to_spawn: u32,
-    pub time_since_last_spawn: f32,
-    pub spawn_interval: f32,
+    pub wave_time: f32, // The script's clock, which the director speeds up or slows down
+    pub wave_elapsed: f32, // Real time since the wave started, for clear times
+    pub expected_clear_time: f32,
+    pub pending_groups: Vec<SpawnGroup>, // Sorted by delay, drained as they spawn
+    pub wave_modifiers: EnemyModifiers,
+    pub boss_wave: bool,
+    pub enemies_killed: u32,
+    pub wave_damage_taken: f32,
+    pub last_reward: Option<WaveReward>,
     pub wave_active: bool,
     pub break_time: f32,
     pub time_in_break: f32,
@@ -36,9 +355,16 @@ impl Default for WaveManager {
         Self {
             current_wave: 1,
             enemies_spawned: 0,
-            enemies_to_spawn: 5, // Start with 5 enemies
-            time_since_last_spawn: 0.0,
-            spawn_interval: 2.0, // Spawn every 2 seconds
+            enemies_to_spawn: 0,
+            wave_time: 0.0,
+            wave_elapsed: 0.0,
+            expected_clear_time: 0.0,
+            pending_groups: Vec::new(),
+            wave_modifiers: EnemyModifiers::default(),
+            boss_wave: false,
+            enemies_killed: 0,
+            wave_damage_taken: 0.0,
+            last_reward: None,
             wave_active: true,
             break_time: 10.0, // 10 second break between waves
             time_in_break: 0.0,
@@ -48,14 +374,12 @@ impl Default for WaveManager {
 }
 
 impl WaveManager {
-    pub fn start_new_wave(&mut self) {
+    pub fn start_new_wave(&mut self, script: &WaveScript, config: &WaveConfig, rng: &mut impl Rng) {
         self.current_wave += 1;
-        self.enemies_spawned = 0;
         
         // Scale difficulty
-        self.difficulty_multiplier = 1.0 + (self.current_wave as f32 - 1.0) * 0.2;
-        self.enemies_to_spawn = 5 + self.current_wave * 3; // More enemies each wave
-        self.spawn_interval = (2.0 - (self.current_wave as f32 * 0.1)).max(0.5); // Faster spawning
+        self.difficulty_multiplier = config.difficulty_multiplier(self.current_wave);
+        self.load_wave(script, config, rng);
         
         self.wave_active = true;
         self.time_in_break = 0.0;
@@ -63,18 +387,52 @@ impl WaveManager {
         info!("Starting wave {} with {} enemies", self.current_wave, self.enemies_to_spawn);
     }
     
+    /// Queues up every spawn group (bosses included) for the current wave
+    pub fn load_wave(&mut self, script: &WaveScript, config: &WaveConfig, rng: &mut impl Rng) {
+        let definition = script.wave(self.current_wave, config, rng);
+        
+        self.boss_wave = !definition.bosses.is_empty();
+        let mut groups = definition.spawn_groups;
+        groups.extend(definition.bosses);
+        groups.sort_by(|a, b| a.delay.total_cmp(&b.delay));
+        
+        self.enemies_to_spawn = groups.iter().map(|group| group.count).sum();
+        // Time to get everything out, plus some slack to finish off the last group
+        self.expected_clear_time = groups.iter().map(|group| group.delay).fold(0.0, f32::max) + 20.0;
+        self.enemies_spawned = 0;
+        self.enemies_killed = 0;
+        self.wave_damage_taken = 0.0;
+        self.wave_time = 0.0;
+        self.wave_elapsed = 0.0;
+        self.pending_groups = groups;
+        self.wave_modifiers = definition.modifiers;
+    }
+    
+    pub fn break_remaining(&self) -> f32 {
+        (self.break_time - self.time_in_break).max(0.0)
+    }
+    
+    /// Score for readying up now instead of waiting out the break
+    pub fn skip_bonus(&self, config: &WaveConfig) -> u32 {
+        self.break_remaining().floor() as u32 * config.break_skip_bonus_per_second * self.current_wave
+    }
+    
     pub fn end_wave(&mut self) {
         self.wave_active = false;
         self.time_in_break = 0.0;
         info!("Wave {} completed! Next wave in {} seconds", self.current_wave, self.break_time);
     }
     
-    pub fn get_spawn_position(&self, arena_bounds: f32) -> Vec3 {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        
-        // Spawn at random position around the arena edge for top-down view
-        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    pub fn get_spawn_position(&self, arena_bounds: f32, edge: SpawnEdge, rng: &mut impl Rng) -> Vec3 {
+        // Spawn around the arena edge for top-down view, within a quarter arc of the chosen side
+        let quarter = std::f32::consts::FRAC_PI_4;
+        let angle = match edge {
+            SpawnEdge::Random => rng.gen_range(0.0..std::f32::consts::TAU),
+            SpawnEdge::East => rng.gen_range(-quarter..quarter),
+            SpawnEdge::South => std::f32::consts::FRAC_PI_2 + rng.gen_range(-quarter..quarter),
+            SpawnEdge::West => std::f32::consts::PI + rng.gen_range(-quarter..quarter),
+            SpawnEdge::North => -std::f32::consts::FRAC_PI_2 + rng.gen_range(-quarter..quarter),
+        };
         let distance = arena_bounds + 50.0; // Just outside the arena
         
         Vec3::new(
@@ -83,143 +441,339 @@ impl WaveManager {
             angle.sin() * distance,
         )
     }
-    
-    pub fn get_enemy_type_for_wave(&self) -> EnemyType {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+}
+
+fn load_wave_script(mut script: ResMut<WaveScript>) {
+    match WaveScript::load_from_file() {
+        Ok(loaded) => {
+            info!("Loaded {} authored waves", loaded.waves.len());
+            *script = loaded;
+        }
+        Err(err) => {
+            warn!("Could not load config/waves.ron ({}), using procedural waves only", err);
+        }
+    }
+}
+
+fn setup_telegraph_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(TelegraphAssets {
+        mesh: meshes.add(Mesh::from(shape::Circle::new(15.0))),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.1, 0.1, 0.5),
+            emissive: Color::RED,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Nudges a spawn point out of asteroids and away from the player
+pub fn resolve_spawn_position(
+    mut position: Vec3,
+    player_position: Option<Vec3>,
+    asteroids: &[(Vec3, f32)],
+    min_player_distance: f32,
+) -> Vec3 {
+    // A few passes, since pushing out of one obstacle can land in another
+    for _ in 0..4 {
+        let mut moved = false;
         
-        match self.current_wave {
-            1..=2 => EnemyType::Chaser,
-            3..=5 => {
-                if rng.gen_bool(0.7) {
-                    EnemyType::Chaser
-                } else {
-                    EnemyType::Swarm
-                }
-            }
-            6..=10 => {
-                let roll = rng.gen_range(0.0..1.0);
-                if roll < 0.4 {
-                    EnemyType::Chaser
-                } else if roll < 0.7 {
-                    EnemyType::Swarm
-                } else {
-                    EnemyType::Shooter
-                }
+        for &(center, radius) in asteroids {
+            let mut offset = position - center;
+            offset.y = 0.0;
+            if offset.length() < radius {
+                let direction = if offset.length() > 0.0 { offset.normalize() } else { Vec3::X };
+                position = Vec3::new(center.x, position.y, center.z) + direction * radius;
+                moved = true;
             }
-            11..=15 => {
-                let roll = rng.gen_range(0.0..1.0);
-                if roll < 0.3 {
-                    EnemyType::Chaser
-                } else if roll < 0.5 {
-                    EnemyType::Swarm
-                } else if roll < 0.8 {
-                    EnemyType::Shooter
-                } else {
-                    EnemyType::Tank
-                }
-            }
-            16..=20 => {
-                let roll = rng.gen_range(0.0..1.0);
-                if roll < 0.2 {
-                    EnemyType::Chaser
-                } else if roll < 0.4 {
-                    EnemyType::Swarm
-                } else if roll < 0.6 {
-                    EnemyType::Shooter
-                } else if roll < 0.8 {
-                    EnemyType::Tank
-                } else {
-                    EnemyType::Elite
-                }
-            }
-            _ => {
-                // Boss waves every 10 waves after wave 20
-                if self.current_wave % 10 == 0 {
-                    EnemyType::Boss
-                } else {
-                    let roll = rng.gen_range(0.0..1.0);
-                    if roll < 0.15 {
-                        EnemyType::Chaser
-                    } else if roll < 0.3 {
-                        EnemyType::Swarm
-                    } else if roll < 0.5 {
-                        EnemyType::Shooter
-                    } else if roll < 0.7 {
-                        EnemyType::Tank
-                    } else {
-                        EnemyType::Elite
-                    }
-                }
+        }
+        
+        if let Some(player_position) = player_position {
+            let mut offset = position - player_position;
+            offset.y = 0.0;
+            if offset.length() < min_player_distance {
+                let direction = if offset.length() > 0.0 { offset.normalize() } else { Vec3::Z };
+                position = Vec3::new(player_position.x, position.y, player_position.z) + direction * min_player_distance;
+                moved = true;
             }
         }
+        
+        if !moved {
+            break;
+        }
     }
+    
+    position
 }
 
-fn initialize_wave_system(mut wave_manager: ResMut<WaveManager>) {
-    info!("Initializing wave system - Wave 1 starting");
-    wave_manager.wave_active = true;
+fn initialize_wave_system(
+    script: Res<WaveScript>,
+    config: Res<WaveConfig>,
+    mut game_rng: ResMut<GameRng>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut boss_events: EventWriter<BossWaveEvent>,
+) {
+    // Only queue wave 1 on a fresh run, not when resuming from pause or a level-up
+    if wave_manager.enemies_spawned == 0 && wave_manager.pending_groups.is_empty() {
+        info!("Initializing wave system - Wave {} starting", wave_manager.current_wave);
+        wave_manager.load_wave(&script, &config, game_rng.spawns());
+        if wave_manager.boss_wave {
+            boss_events.send(BossWaveEvent { wave_number: wave_manager.current_wave });
+        }
+        wave_manager.wave_active = true;
+    }
+    wave_manager.break_time = config.break_duration;
 }
 
+#[allow(clippy::too_many_arguments)]
 fn wave_spawn_system(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     time: Res<Time>,
+    config: Res<WaveConfig>,
+    telegraph_assets: Res<TelegraphAssets>,
+    director: Res<AIDirector>,
+    mut game_rng: ResMut<GameRng>,
     mut wave_manager: ResMut<WaveManager>,
+    player_query: Query<&Transform, With<Player>>,
+    asteroid_query: Query<(&Transform, &Asteroid)>,
 ) {
     if !wave_manager.wave_active {
         return;
     }
     
-    wave_manager.time_since_last_spawn += time.delta_seconds();
+    // The director speeds up or slows down the script's timeline
+    let pace = if director.enabled { director.intensity } else { 1.0 };
+    wave_manager.wave_time += time.delta_seconds() * pace;
+    wave_manager.wave_elapsed += time.delta_seconds();
+    
+    let player_position = player_query.get_single().ok().map(|transform| transform.translation);
+    let asteroids: Vec<(Vec3, f32)> = asteroid_query
+        .iter()
+        .map(|(transform, asteroid)| (transform.translation, asteroid.size * 0.5 + 20.0))
+        .collect();
     
-    if wave_manager.time_since_last_spawn >= wave_manager.spawn_interval 
-        && wave_manager.enemies_spawned < wave_manager.enemies_to_spawn {
+    while wave_manager.pending_groups.first().is_some_and(|group| group.delay <= wave_manager.wave_time) {
+        let mut group = wave_manager.pending_groups.remove(0);
+        let modifiers = wave_manager.wave_modifiers.combine(&group.modifiers);
         
-        let spawn_position = wave_manager.get_spawn_position(400.0);
-        let enemy_type = wave_manager.get_enemy_type_for_wave();
+        let count = director.group_size(group.count);
+        wave_manager.enemies_to_spawn = wave_manager.enemies_to_spawn + count - group.count;
+        group.count = count;
+        
+        // Rings close in around the player, everything else faces into the arena from its edge
+        let (anchor, facing) = match (group.formation, player_position) {
+            (Formation::Ring, Some(player_position)) => (Vec3::new(player_position.x, 0.0, player_position.z), Quat::IDENTITY),
+            _ => {
+                let anchor = wave_manager.get_spawn_position(400.0, group.edge, game_rng.spawns());
+                (anchor, Quat::from_rotation_arc(Vec3::Z, (-anchor).normalize_or_zero()))
+            }
+        };
         
-        spawn_enemy(&mut commands, &asset_server, enemy_type, spawn_position);
+        for offset in group.formation.offsets(group.count) {
+            let position = resolve_spawn_position(
+                anchor + facing * offset,
+                player_position,
+                &asteroids,
+                config.min_spawn_distance,
+            );
+            
+            // Enemies appear once their warning marker runs out
+            commands.spawn((
+                PbrBundle {
+                    mesh: telegraph_assets.mesh.clone(),
+                    material: telegraph_assets.material.clone(),
+                    transform: Transform::from_translation(position + Vec3::Y * 0.05)
+                        .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+                    ..default()
+                },
+                SpawnTelegraph {
+                    enemy_type: director.adjust_enemy_type(group.enemy_type.clone(), game_rng.spawns()),
+                    modifiers: modifiers.clone(),
+                    remaining: config.spawn_telegraph_time,
+                    duration: config.spawn_telegraph_time,
+                },
+                GameEntity,
+                Name::new("SpawnTelegraph"),
+            ));
+            wave_manager.enemies_spawned += 1;
+        }
+        
+        info!("Telegraphed {} {:?} in {:?} formation ({} of {})",
+            group.count, group.enemy_type, group.formation,
+            wave_manager.enemies_spawned, wave_manager.enemies_to_spawn);
+    }
+}
+
+pub fn spawn_telegraph_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut game_rng: ResMut<GameRng>,
+    mut telegraph_query: Query<(Entity, &mut Transform, &mut SpawnTelegraph)>,
+) {
+    for (entity, mut transform, mut telegraph) in telegraph_query.iter_mut() {
+        telegraph.remaining -= time.delta_seconds();
         
-        wave_manager.enemies_spawned += 1;
-        wave_manager.time_since_last_spawn = 0.0;
+        if telegraph.remaining <= 0.0 {
+            let position = Vec3::new(transform.translation.x, 0.0, transform.translation.z);
+            spawn_enemy_with_modifiers(
+                &mut commands,
+                &asset_server,
+                game_rng.spawns(),
+                telegraph.enemy_type.clone(),
+                position,
+                &telegraph.modifiers,
+            );
+            commands.entity(entity).despawn();
+            continue;
+        }
         
-        info!("Spawned enemy {} of {}", wave_manager.enemies_spawned, wave_manager.enemies_to_spawn);
+        // Pulse faster as the spawn gets closer
+        let progress = 1.0 - telegraph.remaining / telegraph.duration;
+        let pulse = 1.0 + (progress * progress * 40.0).sin() * 0.15;
+        transform.scale = Vec3::splat((0.5 + progress * 0.5) * pulse);
     }
 }
 
 fn wave_progression_system(
     time: Res<Time>,
+    script: Res<WaveScript>,
+    config: Res<WaveConfig>,
+    mut game_rng: ResMut<GameRng>,
     mut wave_manager: ResMut<WaveManager>,
+    mut boss_events: EventWriter<BossWaveEvent>,
 ) {
     if !wave_manager.wave_active {
         wave_manager.time_in_break += time.delta_seconds();
         
         if wave_manager.time_in_break >= wave_manager.break_time {
-            wave_manager.start_new_wave();
+            wave_manager.start_new_wave(&script, &config, game_rng.spawns());
+            if wave_manager.boss_wave {
+                boss_events.send(BossWaveEvent { wave_number: wave_manager.current_wave });
+            }
         }
     }
 }
 
+fn break_ready_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<WaveConfig>,
+    mut game_data: ResMut<GameData>,
+    mut wave_manager: ResMut<WaveManager>,
+) {
+    if wave_manager.wave_active || !keyboard_input.just_pressed(KeyCode::Return) {
+        return;
+    }
+    
+    let bonus = wave_manager.skip_bonus(&config);
+    game_data.score += bonus;
+    info!("Player ready - skipping {:.1}s of break for {} points", wave_manager.break_remaining(), bonus);
+    
+    // Run out the clock so the next wave starts straight away
+    wave_manager.time_in_break = wave_manager.break_time;
+}
+
 fn check_wave_completion_system(
     enemy_query: Query<&crate::enemy::Enemy>,
+    telegraph_query: Query<&SpawnTelegraph>,
     mut wave_manager: ResMut<WaveManager>,
+    mut director: ResMut<AIDirector>,
+    mut wave_events: EventWriter<WaveCompleteEvent>,
 ) {
     if wave_manager.wave_active && wave_manager.enemies_spawned >= wave_manager.enemies_to_spawn {
-        // Check if all enemies are dead
-        let enemies_alive = enemy_query.iter().count();
+        // Check if all enemies are dead and none are still about to appear
+        let enemies_alive = enemy_query.iter().count() + telegraph_query.iter().count();
         
         if enemies_alive == 0 {
+            director.last_clear_time = Some(wave_manager.wave_elapsed);
+            director.last_expected_clear_time = wave_manager.expected_clear_time;
+            wave_events.send(WaveCompleteEvent {
+                wave_number: wave_manager.current_wave,
+                enemies_killed: wave_manager.enemies_killed,
+                clear_time: wave_manager.wave_elapsed,
+                expected_clear_time: wave_manager.expected_clear_time,
+                damage_taken: wave_manager.wave_damage_taken,
+            });
             wave_manager.end_wave();
         }
     }
 }
 
+fn record_kill_stats_system(
+    mut wave_manager: ResMut<WaveManager>,
+    mut wave_stats: ResMut<WaveStats>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+) {
+    for _ in killed_events.read() {
+        wave_stats.enemy_killed();
+        wave_manager.enemies_killed += 1;
+    }
+    wave_stats.wave_reached(wave_manager.current_wave);
+}
+
+fn initialize_director_system(
+    config: Res<WaveConfig>,
+    mut director: ResMut<AIDirector>,
+) {
+    // Fixed/ranked runs play the script exactly as written
+    director.enabled = config.director_enabled;
+    if !director.enabled {
+        director.intensity = 1.0;
+    }
+}
+
+pub fn director_update_system(
+    time: Res<Time>,
+    config: Res<WaveConfig>,
+    mut director: ResMut<AIDirector>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut wave_stats: ResMut<WaveStats>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    player_query: Query<&Player>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+    
+    // Smooth over roughly the last ten seconds
+    let smoothing = (dt / 10.0).min(1.0);
+    let kills = killed_events.read().count() as f32;
+    director.kill_rate += (kills / dt - director.kill_rate) * smoothing;
+    
+    if let Ok(player) = player_query.get_single() {
+        // Damage shows up as a drop in health + shields; regen and pickups only ever raise it
+        let total_health = player.health + player.shields;
+        let damage = director.last_total_health.map_or(0.0, |last| (last - total_health).max(0.0));
+        director.last_total_health = Some(total_health);
+        wave_stats.add_damage_taken(damage);
+        wave_manager.wave_damage_taken += damage;
+        
+        let damage_fraction = damage / player.max_health.max(1.0);
+        director.damage_taken_rate += (damage_fraction / dt - director.damage_taken_rate) * smoothing;
+        director.health_fraction = (player.health / player.max_health).clamp(0.0, 1.0);
+    }
+    
+    if director.enabled {
+        let target = director.target_intensity(&config);
+        let intensity = director.intensity + (target - director.intensity) * (config.director_adjust_rate * dt).min(1.0);
+        director.intensity = intensity.clamp(config.director_min_intensity, config.director_max_intensity);
+    }
+}
+
 // Special wave events
 #[derive(Event)]
 pub struct WaveCompleteEvent {
     pub wave_number: u32,
     pub enemies_killed: u32,
+    pub clear_time: f32,
+    pub expected_clear_time: f32,
+    pub damage_taken: f32,
 }
 
 #[derive(Event)]
@@ -227,13 +781,60 @@ pub struct BossWaveEvent {
     pub wave_number: u32,
 }
 
+// What the player earned for clearing a wave, shown in the "Wave Cleared" banner
+#[derive(Clone, Debug)]
+pub struct WaveReward {
+    pub wave_number: u32,
+    pub bonus_experience: f32,
+    pub fast_clear_bonus: u32,
+    pub flawless_bonus: u32,
+}
+
+impl WaveReward {
+    pub fn for_wave(event: &WaveCompleteEvent) -> Self {
+        let fast_clear = event.clear_time <= event.expected_clear_time * 0.75;
+        let flawless = event.damage_taken <= 0.0;
+        
+        Self {
+            wave_number: event.wave_number,
+            bonus_experience: 20.0 * event.wave_number as f32,
+            fast_clear_bonus: if fast_clear { 50 * event.wave_number } else { 0 },
+            flawless_bonus: if flawless { 100 * event.wave_number } else { 0 },
+        }
+    }
+    
+    pub fn score_bonus(&self) -> u32 {
+        self.fast_clear_bonus + self.flawless_bonus
+    }
+}
+
 // Systems for special events
 pub fn handle_wave_complete(
+    mut commands: Commands,
+    loot_assets: Res<LootAssets>,
+    mut game_data: ResMut<GameData>,
+    mut wave_manager: ResMut<WaveManager>,
     mut wave_events: EventReader<WaveCompleteEvent>,
+    mut player_query: Query<(&Transform, &mut Player)>,
 ) {
     for event in wave_events.read() {
         info!("Wave {} completed! {} enemies defeated", event.wave_number, event.enemies_killed);
-        // TODO: Award bonus experience, loot, etc.
+        
+        let reward = WaveReward::for_wave(event);
+        game_data.score += reward.score_bonus();
+        
+        if let Ok((transform, mut player)) = player_query.get_single_mut() {
+            if player.add_experience(reward.bonus_experience) {
+                info!("Player leveled up to level {}!", player.level);
+            }
+            
+            // Drop the chest just ahead of the player so it's easy to grab during the break
+            let position = Vec3::new(transform.translation.x, 0.0, transform.translation.z)
+                + transform.rotation * Vec3::Z * 60.0;
+            spawn_loot_chest(&mut commands, &loot_assets, position, event.wave_number);
+        }
+        
+        wave_manager.last_reward = Some(reward);
     }
 }
 
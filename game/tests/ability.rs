@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use void_survivor::player::{Player, PlayerAim, Projectile};
+use void_survivor::testing::TestApp;
+use void_survivor::upgrade::{SpecialAbilities, SpecialAbility, Upgrade, UpgradeManager, UpgradeType};
+
+const PLAYER_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+fn take(player: &mut Player, ability: SpecialAbility, times: u32) {
+    let upgrade = Upgrade::new(UpgradeType::SpecialAbility(ability), 1);
+    for _ in 0..times {
+        UpgradeManager::default().apply_upgrade(&upgrade, player);
+    }
+}
+
+// Holds fire just long enough for the Blaster's first volley (5 shots a second)
+fn fire_once(test: &mut TestApp) {
+    test.resource_mut::<PlayerAim>().cursor_offset = Some(Vec2::new(100.0, 0.0));
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::Space);
+    test.advance_ticks(15);
+    test.resource_mut::<Input<KeyCode>>().release(KeyCode::Space);
+}
+
+#[test]
+fn every_stack_helps_less_than_the_last() {
+    let with = |stacks: u32| SpecialAbilities {
+        double_shot: stacks,
+        piercing_shots: stacks,
+        explosive_shots: stacks,
+        shield_regeneration: stacks,
+        dash: stacks,
+    };
+    let gains = |value: fn(&SpecialAbilities) -> f32| {
+        let values: Vec<f32> = (0..5).map(|stacks| value(&with(stacks))).collect();
+        values.windows(2).map(|pair| pair[1] - pair[0]).collect::<Vec<f32>>()
+    };
+
+    for gain in [
+        gains(|abilities| abilities.splash_radius()),
+        gains(|abilities| abilities.shield_regen_multiplier()),
+        gains(|abilities| abilities.shot_damage_scale() * (1 + abilities.double_shot) as f32),
+    ] {
+        assert!(gain.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", gain);
+    }
+    assert_eq!(with(0).dash_cost(), None);
+    assert!(with(2).dash_cost() < with(1).dash_cost());
+    assert!(with(4).extra_pierce() > with(1).extra_pierce());
+}
+
+#[test]
+fn double_shot_fires_side_by_side_copies() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    take(&mut test.get_mut::<Player>(player).unwrap(), SpecialAbility::DoubleShot, 1);
+
+    fire_once(&mut test);
+
+    let shots = test.all::<Projectile>();
+    assert_eq!(shots.len(), 2);
+    // The first extra copy is a full-strength shot
+    assert!(shots.iter().all(|shot| shot.damage == 20.0 && shot.velocity == shots[0].velocity));
+}
+
+#[test]
+fn piercing_and_explosive_shots_carry_onto_projectiles() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    {
+        let mut player = test.get_mut::<Player>(player).unwrap();
+        take(&mut player, SpecialAbility::PiercingShots, 1);
+        take(&mut player, SpecialAbility::ExplosiveShots, 2);
+        // Only the first stack adds the burn
+        assert_eq!(player.on_hit_effects.len(), 1);
+    }
+
+    fire_once(&mut test);
+
+    let shots = test.all::<Projectile>();
+    assert_eq!(shots.len(), 1);
+    assert_eq!(shots[0].pierce, 2);
+    assert_eq!(shots[0].splash_radius, 80.0);
+}
+
+#[test]
+fn shield_regeneration_starts_sooner_and_runs_faster() {
+    let regen_after = |stacks: u32, ticks: u32| {
+        let mut test = TestApp::new();
+        let player = test.spawn_player(PLAYER_POSITION);
+        {
+            let mut player = test.get_mut::<Player>(player).unwrap();
+            take(&mut player, SpecialAbility::ShieldRegeneration, stacks);
+            let shields = player.shields;
+            player.take_damage(shields);
+        }
+        test.advance_ticks(ticks);
+        test.get::<Player>(player).unwrap().shields
+    };
+
+    // The base delay is 3 seconds, so nothing after 2.5
+    assert_eq!(regen_after(0, 150), 0.0);
+    assert!(regen_after(1, 150) > 0.0);
+    assert!(regen_after(2, 240) > regen_after(0, 240));
+}
+
+#[test]
+fn dash_bursts_forward_and_dodges_damage() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::ShiftLeft);
+
+    // Nothing happens until the ability is taken
+    test.tick();
+    assert_eq!(test.get::<Velocity>(player).unwrap().linvel, Vec3::ZERO);
+
+    take(&mut test.get_mut::<Player>(player).unwrap(), SpecialAbility::Dash, 1);
+    test.tick();
+    test.resource_mut::<Input<KeyCode>>().release(KeyCode::ShiftLeft);
+
+    let speed = test.get::<Velocity>(player).unwrap().linvel.length();
+    assert!(speed > 300.0, "{}", speed);
+    let mut ship = test.get_mut::<Player>(player).unwrap();
+    // Half the energy gone, less the regen since
+    assert!((ship.special_energy - 50.0).abs() < 1.0);
+    let health = ship.health + ship.shields;
+    ship.take_damage(40.0);
+    assert_eq!(ship.health + ship.shields, health);
+}
@@ -0,0 +1,29 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use void_survivor::audio::{AudioManager, MusicTrack, INTENSE_MUSIC_ENTER, INTENSE_MUSIC_EXIT};
+use void_survivor::testing::TestApp;
+
+#[test]
+fn intense_music_holds_until_intensity_clearly_drops() {
+    let between = (INTENSE_MUSIC_ENTER + INTENSE_MUSIC_EXIT) / 2.0;
+
+    // Rising: only crossing the enter threshold brings the intense track in
+    assert!(matches!(MusicTrack::for_intensity(between, false), MusicTrack::GameplayAmbient));
+    assert!(matches!(MusicTrack::for_intensity(INTENSE_MUSIC_ENTER, false), MusicTrack::GameplayIntense));
+
+    // Falling: it stays until intensity is below the exit threshold
+    assert!(matches!(MusicTrack::for_intensity(between, true), MusicTrack::GameplayIntense));
+    assert!(matches!(MusicTrack::for_intensity(INTENSE_MUSIC_EXIT - 0.01, true), MusicTrack::GameplayAmbient));
+}
+
+#[test]
+fn missing_music_file_plays_nothing() {
+    let mut test = TestApp::new();
+    test.app.world.run_system_once(|mut commands: Commands, asset_server: Res<AssetServer>| {
+        let mut audio_manager = AudioManager::new();
+        audio_manager.play_music(&mut commands, &asset_server, MusicTrack::GameplayAmbient.as_str());
+        assert!(audio_manager.current_music.is_none());
+    });
+
+    assert!(test.all::<PlaybackSettings>().is_empty());
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use void_survivor::affix::{Corrosive, Freezing};
+use void_survivor::enemy::{Enemy, EnemyKilledEvent, EnemyType};
+use void_survivor::player::Player;
+use void_survivor::status::{StatusEffectType, StatusEffects};
+use void_survivor::testing::TestApp;
+
+#[test]
+fn projectile_hit_damages_enemy_and_is_spent() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(-300.0, 0.5, 0.0));
+    let enemy = test.spawn_enemy(EnemyType::Tank, Vec3::new(100.0, 0.0, 0.0));
+    let projectile = test.spawn_projectile(player, Vec3::new(90.0, 0.0, 0.0), Vec3::X, 40.0);
+
+    test.tick();
+
+    let tank = test.get::<Enemy>(enemy).expect("tank survives one hit");
+    assert_eq!(tank.health, 110.0);
+    assert_eq!(tank.last_attacker, Some(player));
+    assert!(!test.exists(projectile));
+}
+
+#[test]
+fn lethal_hit_kills_enemy_and_sends_killed_event() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(-300.0, 0.5, 0.0));
+    let enemy = test.spawn_enemy(EnemyType::Swarm, Vec3::new(100.0, 0.0, 0.0));
+    test.spawn_projectile(player, Vec3::new(90.0, 0.0, 0.0), Vec3::X, 40.0);
+
+    test.tick();
+
+    assert!(!test.exists(enemy));
+    let killed = test.events::<EnemyKilledEvent>();
+    assert_eq!(killed.len(), 1);
+    assert_eq!(killed[0].enemy_type, EnemyType::Swarm);
+    assert_eq!(killed[0].killer, Some(player));
+}
+
+#[test]
+fn projectile_misses_distant_enemy() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(-300.0, 0.5, 0.0));
+    let enemy = test.spawn_enemy(EnemyType::Chaser, Vec3::new(100.0, 0.0, 200.0));
+    test.spawn_projectile(player, Vec3::new(90.0, 0.0, 0.0), Vec3::X, 40.0);
+
+    test.advance_ticks(5);
+
+    assert_eq!(test.get::<Enemy>(enemy).map(|enemy| enemy.health), Some(50.0));
+}
+
+#[test]
+fn freezing_and_corrosive_shots_leave_their_effects_on_the_player() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let shooter = test.spawn_enemy(EnemyType::Shooter, Vec3::new(200.0, 0.0, 0.0));
+    test.app.world.entity_mut(shooter).insert((Freezing, Corrosive));
+
+    // Until the first shot lands
+    let mut ticks = 0;
+    while test.get::<StatusEffects>(player).unwrap().effects.is_empty() && ticks < 600 {
+        test.tick();
+        ticks += 1;
+    }
+
+    let hit = test.get::<Player>(player).unwrap();
+    assert!(hit.health + hit.shields < hit.max_health + hit.max_shields);
+    let status = test.get::<StatusEffects>(player).unwrap();
+    assert!(status.is_frozen());
+    assert_eq!(status.speed_multiplier(), 0.0);
+    assert_eq!(status.stacks(StatusEffectType::Vulnerability), 1);
+    assert!(status.damage_taken_multiplier() > 1.0);
+}
@@ -0,0 +1,296 @@
+use bevy::prelude::*;
+use void_survivor::GameMode;
+use void_survivor::config::GameConfig;
+use void_survivor::enemy::{Enemy, EnemyType};
+use void_survivor::loot::{experience_gem_scale, Loot, LootTables, LootType, LootVisual, Rarity, RarityWeights};
+use void_survivor::player::Player;
+use void_survivor::rng::GameRng;
+use void_survivor::survival::SurvivalRun;
+use void_survivor::testing::TestApp;
+
+fn kill(test: &mut TestApp, enemy_type: EnemyType, position: Vec3) {
+    let enemy = test.spawn_enemy(enemy_type, position);
+    test.get_mut::<Enemy>(enemy).unwrap().health = 0.0;
+}
+
+// The tuned tables the game ships with, rather than the experience-only fallback
+fn shipped_tables() -> LootTables {
+    ron::de::from_str(include_str!("../../config/loot_tables.ron")).unwrap()
+}
+
+fn test_app_with_shipped_tables() -> TestApp {
+    let mut test = TestApp::new();
+    test.app.insert_resource(shipped_tables());
+    test
+}
+
+// Drops from the tables, leaving out the experience orb every kill leaves
+fn table_drops(test: &mut TestApp) -> Vec<Rarity> {
+    test.all::<Loot>()
+        .into_iter()
+        .filter(|loot| loot.loot_type != LootType::Experience)
+        .map(|loot| loot.rarity)
+        .collect()
+}
+
+#[test]
+fn experience_pickup_levels_up_player() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let orb = test.spawn_loot(LootType::Experience, Vec3::new(10.0, 0.0, 0.0), 120.0);
+
+    test.tick();
+
+    assert!(!test.exists(orb));
+    let player = test.get::<Player>(player).unwrap();
+    assert_eq!(player.level, 2);
+    assert_eq!(player.experience, 20.0);
+}
+
+#[test]
+fn health_pickup_is_capped_at_max_health() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.get_mut::<Player>(player).unwrap().health = 90.0;
+    test.spawn_loot(LootType::Health, Vec3::new(0.0, 0.0, 10.0), 30.0);
+
+    test.tick();
+
+    assert_eq!(test.get::<Player>(player).unwrap().health, 100.0);
+}
+
+#[test]
+fn magnet_pulls_nearby_loot_in_to_be_picked_up() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.get_mut::<Player>(player).unwrap().shields = 0.0;
+    let loot = test.spawn_loot(LootType::Shield, Vec3::new(80.0, 0.0, 0.0), 20.0);
+
+    test.tick();
+    assert!(test.exists(loot), "out of pickup range at first");
+
+    test.advance_ticks(60);
+
+    assert!(!test.exists(loot));
+    assert!(test.get::<Player>(player).unwrap().shields >= 20.0);
+}
+
+#[test]
+fn loot_out_of_range_stays_put() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let loot = test.spawn_loot(LootType::Health, Vec3::new(300.0, 0.0, 0.0), 20.0);
+
+    test.advance_ticks(30);
+
+    assert!(test.exists(loot));
+}
+
+#[test]
+fn shipped_loot_tables_parse_and_cover_every_enemy() {
+    let tables = shipped_tables();
+
+    for enemy_type in [EnemyType::Chaser, EnemyType::Swarm, EnemyType::Shooter, EnemyType::Tank, EnemyType::Elite, EnemyType::Boss] {
+        assert!(tables.table(&enemy_type).is_some(), "no loot table for {:?}", enemy_type);
+    }
+}
+
+#[test]
+fn boss_always_drops_at_least_epic() {
+    let mut test = test_app_with_shipped_tables();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    kill(&mut test, EnemyType::Boss, Vec3::new(300.0, 0.0, 0.0));
+
+    test.advance_ticks(2);
+
+    let drops = table_drops(&mut test);
+    assert_eq!(drops.len(), 3);
+    assert!(drops.iter().all(|rarity| *rarity >= Rarity::Epic));
+}
+
+#[test]
+fn only_rare_and_better_drops_carry_a_light() {
+    let mut test = test_app_with_shipped_tables();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    for i in 0..20 {
+        test.spawn_loot(LootType::Experience, Vec3::new(200.0 + i as f32 * 10.0, 0.0, 200.0), 1.0);
+    }
+    kill(&mut test, EnemyType::Boss, Vec3::new(300.0, 0.0, 0.0));
+
+    test.advance_ticks(2);
+
+    // Only the boss's three Epic drops light up; the orbs glow through their material
+    assert_eq!(test.all::<PointLight>().len(), 3);
+}
+
+#[test]
+fn pity_timer_guarantees_a_drop_after_a_dry_streak() {
+    let mut test = test_app_with_shipped_tables();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<GameConfig>().loot_chance_multiplier = 0.0;
+    test.resource_mut::<LootTables>().pity_kills = 3;
+
+    kill(&mut test, EnemyType::Chaser, Vec3::new(300.0, 0.0, 0.0));
+    kill(&mut test, EnemyType::Chaser, Vec3::new(-300.0, 0.0, 0.0));
+    test.advance_ticks(2);
+    assert!(table_drops(&mut test).is_empty());
+
+    kill(&mut test, EnemyType::Chaser, Vec3::new(0.0, 0.0, 300.0));
+    test.advance_ticks(2);
+    assert_eq!(table_drops(&mut test).len(), 1);
+}
+
+#[test]
+fn later_waves_roll_better_rarities() {
+    let tables = shipped_tables();
+    let mut game_rng = GameRng::new(7);
+    let mut better_than_common = |wave: u32| {
+        (0..2000)
+            .filter(|_| tables.roll_rarity(wave, 1.0, game_rng.loot()) > Rarity::Common)
+            .count()
+    };
+
+    let early = better_than_common(1);
+    let late = better_than_common(30);
+    assert!(late > early, "wave 30 rolled {} non-common, wave 1 rolled {}", late, early);
+}
+
+#[test]
+fn survival_rarity_improves_with_time_survived() {
+    let mut test = test_app_with_shipped_tables();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.app.insert_resource(GameMode::Survival);
+    test.resource_mut::<SurvivalRun>().elapsed = 600.0;
+    {
+        // Even odds at the start, next to no Commons ten minutes in
+        let mut tables = test.resource_mut::<LootTables>();
+        tables.rarity_weights = RarityWeights { common: 1.0, rare: 1.0, epic: 0.0, legendary: 0.0 };
+        tables.rarity_scaling_per_wave = 1000.0;
+        tables.pity_kills = 1;
+    }
+    assert_eq!(test.resource::<LootTables>().survival_wave(600.0), 11);
+
+    for i in 0..10 {
+        kill(&mut test, EnemyType::Chaser, Vec3::new(300.0, 0.0, i as f32 * 40.0));
+    }
+    test.advance_ticks(2);
+
+    let drops = table_drops(&mut test);
+    assert_eq!(drops.len(), 10);
+    assert!(drops.iter().all(|rarity| *rarity == Rarity::Rare), "{:?}", drops);
+}
+
+#[test]
+fn uncollected_loot_fades_then_expires() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<GameConfig>().loot_lifetime = 3.0;
+    let loot = test.spawn_loot(LootType::Health, Vec3::new(300.0, 0.0, 0.0), 20.0);
+
+    // Two seconds of fading, starting one second in
+    test.advance_ticks(120);
+    let visual = test.get::<Children>(loot).unwrap()
+        .iter()
+        .copied()
+        .find(|&child| test.get::<LootVisual>(child).is_some())
+        .expect("pickup has a model");
+    let material = test.get::<Handle<StandardMaterial>>(visual).unwrap().clone();
+    let alpha = test.resource::<Assets<StandardMaterial>>().get(&material).unwrap().base_color.a();
+    assert!(alpha > 0.0 && alpha < 1.0, "alpha {} mid-fade", alpha);
+
+    test.advance_ticks(61);
+    assert!(!test.exists(loot));
+    assert!(!test.exists(visual));
+}
+
+#[test]
+fn fading_loot_dims_its_light_too() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<GameConfig>().loot_lifetime = 3.0;
+    test.spawn_loot_of_rarity(LootType::Health, Rarity::Legendary, Vec3::new(300.0, 0.0, 0.0), 20.0);
+    let full = Rarity::Legendary.glow().1;
+
+    test.advance_ticks(30);
+    assert_eq!(test.all::<PointLight>()[0].intensity, full);
+
+    test.advance_ticks(90);
+    let intensity = test.all::<PointLight>()[0].intensity;
+    assert!(intensity > 0.0 && intensity < full, "intensity {} mid-fade", intensity);
+}
+
+#[test]
+fn picking_loot_up_removes_its_model_too() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let loot = test.spawn_loot(LootType::Shield, Vec3::new(10.0, 0.0, 0.0), 20.0);
+    let children: Vec<Entity> = test.get::<Children>(loot).unwrap().iter().copied().collect();
+
+    test.tick();
+
+    assert!(!test.exists(loot));
+    assert!(children.iter().all(|&child| !test.exists(child)));
+}
+
+#[test]
+fn magnet_range_is_a_player_stat() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let loot = test.spawn_loot(LootType::Experience, Vec3::new(150.0, 0.0, 0.0), 10.0);
+
+    test.advance_ticks(5);
+    assert_eq!(test.get::<Transform>(loot).unwrap().translation.x, 150.0, "outside the default range");
+
+    test.get_mut::<Player>(player).unwrap().magnet_range = 200.0;
+    test.advance_ticks(5);
+
+    assert!(test.get::<Transform>(loot).unwrap().translation.x < 150.0);
+}
+
+#[test]
+fn magnet_pulls_harder_up_close() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let far = test.spawn_loot(LootType::Experience, Vec3::new(90.0, 0.0, 0.0), 10.0);
+    let near = test.spawn_loot(LootType::Experience, Vec3::new(-50.0, 0.0, 0.0), 10.0);
+
+    test.tick();
+
+    let far_step = 90.0 - test.get::<Transform>(far).unwrap().translation.x;
+    let near_step = test.get::<Transform>(near).unwrap().translation.x + 50.0;
+    assert!(near_step > far_step * 1.5, "near {} far {}", near_step, far_step);
+}
+
+#[test]
+fn excess_experience_orbs_merge_into_gems() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<GameConfig>().max_experience_orbs = 3;
+    for i in 0..3 {
+        test.spawn_loot(LootType::Experience, Vec3::new(300.0 + i as f32 * 10.0, 0.0, 300.0), 10.0);
+        test.spawn_loot(LootType::Experience, Vec3::new(-300.0, 0.0, 300.0 + i as f32 * 10.0), 10.0);
+    }
+
+    test.tick();
+    test.tick(); // Merged orbs are gone once the despawns apply
+
+    let orbs: Vec<f32> = test.all::<Loot>().into_iter().map(|loot| loot.value).collect();
+    assert_eq!(orbs, vec![30.0, 30.0]);
+    let mut visuals = test.app.world.query_filtered::<&Transform, With<LootVisual>>();
+    for transform in visuals.iter(&test.app.world) {
+        assert_eq!(transform.scale, Vec3::splat(experience_gem_scale(30.0)));
+    }
+    assert!(experience_gem_scale(30.0) > experience_gem_scale(10.0));
+}
+
+#[test]
+fn fallback_tables_only_drop_common_experience() {
+    let tables = LootTables::default();
+    let mut game_rng = GameRng::new(7);
+
+    for enemy_type in [EnemyType::Chaser, EnemyType::Swarm, EnemyType::Shooter, EnemyType::Tank, EnemyType::Elite, EnemyType::Boss] {
+        let table = tables.table(&enemy_type).unwrap();
+        assert!(table.entries.iter().all(|entry| entry.loot_type == LootType::Experience), "{:?}", enemy_type);
+    }
+    assert!((0..100).all(|_| tables.roll_rarity(30, 1.0, game_rng.loot()) == Rarity::Common));
+}
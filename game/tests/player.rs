@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use void_survivor::GameState;
+use void_survivor::player::Player;
+use void_survivor::replay::{Replay, ReplayRecorder};
+use void_survivor::rng::GameRng;
+use void_survivor::testing::TestApp;
+
+#[test]
+fn shields_absorb_damage_before_health() {
+    let mut player = Player::new();
+
+    player.take_damage(30.0);
+    assert_eq!(player.shields, 20.0);
+    assert_eq!(player.health, 100.0);
+
+    // The hit that breaks the shields carries the rest through to health
+    player.take_damage(50.0);
+    assert_eq!(player.shields, 0.0);
+    assert_eq!(player.health, 70.0);
+    assert!(player.is_alive);
+}
+
+#[test]
+fn player_dies_when_health_runs_out() {
+    let mut player = Player::new();
+
+    player.take_damage(player.shields + player.health);
+    assert!(player.health <= 0.0);
+    assert!(!player.is_alive);
+}
+
+#[test]
+fn experience_levels_up_and_keeps_the_overflow() {
+    let mut player = Player::new();
+
+    // Level 1 needs 100
+    assert!(!player.add_experience(60.0));
+    assert_eq!(player.level, 1);
+
+    assert!(player.add_experience(60.0));
+    assert_eq!(player.level, 2);
+    assert_eq!(player.experience, 20.0);
+
+    // Level 2 needs 200
+    assert!(!player.add_experience(150.0));
+    assert!(player.add_experience(30.0));
+    assert_eq!(player.level, 3);
+    assert_eq!(player.experience, 0.0);
+}
+
+#[test]
+fn death_ends_the_run_and_saves_its_replay() {
+    let mut test = TestApp::new();
+    let directory = std::env::temp_dir().join(format!("void_survivor_replays_{}", std::process::id()));
+    {
+        let mut recorder = test.resource_mut::<ReplayRecorder>();
+        recorder.enabled = true;
+        recorder.directory = directory.clone();
+    }
+    test.start_new_run();
+    test.advance_ticks(30);
+    let seed = test.resource::<GameRng>().seed();
+
+    let mut query = test.app.world.query::<&mut Player>();
+    query.single_mut(&mut test.app.world).take_damage(1000.0);
+    test.advance_ticks(2);
+
+    assert_eq!(*test.resource::<State<GameState>>().get(), GameState::GameOver);
+    let path = test.resource::<ReplayRecorder>().last_saved.clone().expect("the replay is saved on game over");
+    let replay = Replay::load(&path).unwrap();
+    assert_eq!(replay.seed, seed);
+    assert!(replay.frames.len() >= 31);
+    std::fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use void_survivor::enemy::{Enemy, EnemyType};
+use void_survivor::loot::LootType;
+use void_survivor::player::Player;
+use void_survivor::powerup::{ActiveBuff, PowerUpConfig, PowerUpType};
+use void_survivor::testing::TestApp;
+
+const PLAYER_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+// Drops a power-up on the player and waits for it to reach their stats: picked up on the first
+// tick, folded into `Player::buffs` on the next
+fn collect(test: &mut TestApp, power_up: PowerUpType, value: f32) {
+    test.spawn_loot(LootType::PowerUp(power_up), Vec3::ZERO, value);
+    test.advance_ticks(2);
+}
+
+fn buff(test: &mut TestApp, power_up: PowerUpType) -> Option<ActiveBuff> {
+    test.all::<ActiveBuff>()
+        .into_iter()
+        .find(|buff| buff.source == power_up)
+        .cloned()
+}
+
+#[test]
+fn overdrive_stacks_up_to_its_limit() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+
+    collect(&mut test, PowerUpType::Overdrive, 1.0);
+    assert_eq!(test.get::<Player>(player).unwrap().buffs.fire_rate_multiplier, 2.0);
+
+    // Two more in the same tick still only reach the two-stack limit
+    test.spawn_loot(LootType::PowerUp(PowerUpType::Overdrive), Vec3::ZERO, 1.0);
+    collect(&mut test, PowerUpType::Overdrive, 1.0);
+
+    assert_eq!(test.all::<ActiveBuff>().len(), 1);
+    assert_eq!(buff(&mut test, PowerUpType::Overdrive).unwrap().stacks, 2);
+    assert_eq!(test.get::<Player>(player).unwrap().buffs.fire_rate_multiplier, 4.0);
+}
+
+#[test]
+fn time_slow_extends_instead_of_refreshing() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    let duration = PowerUpConfig::default().time_slow.duration;
+
+    collect(&mut test, PowerUpType::TimeSlow, 1.0);
+    collect(&mut test, PowerUpType::TimeSlow, 1.0);
+
+    let remaining = buff(&mut test, PowerUpType::TimeSlow).unwrap().remaining;
+    assert!(remaining > duration * 1.9 && remaining < duration * 2.0, "remaining {}", remaining);
+}
+
+#[test]
+fn time_slow_halves_enemy_speed() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    collect(&mut test, PowerUpType::TimeSlow, 1.0);
+    let enemy = test.spawn_enemy(EnemyType::Tank, Vec3::new(400.0, 0.5, 0.0));
+
+    test.advance_ticks(60);
+
+    let speed = test.get::<Enemy>(enemy).unwrap().speed;
+    let travelled = 400.0 - test.get::<Transform>(enemy).unwrap().translation.x;
+    assert!((travelled - speed * 0.5).abs() < 1.0, "travelled {} at speed {}", travelled, speed);
+}
+
+#[test]
+fn invulnerability_blocks_damage() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    collect(&mut test, PowerUpType::Invulnerability, 1.0);
+
+    test.get_mut::<Player>(player).unwrap().take_damage(500.0);
+
+    let player = test.get::<Player>(player).unwrap();
+    assert_eq!(player.health, player.max_health);
+    assert!(player.is_alive);
+}
+
+#[test]
+fn buffs_wear_off() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    collect(&mut test, PowerUpType::QuadDamage, 0.05); // Half a second
+    assert_eq!(test.get::<Player>(player).unwrap().buffs.damage_multiplier, 4.0);
+
+    test.advance_ticks(40);
+
+    assert!(test.all::<ActiveBuff>().is_empty());
+    assert_eq!(test.get::<Player>(player).unwrap().buffs.damage_multiplier, 1.0);
+}
+
+#[test]
+fn nuke_destroys_enemies_in_range_only() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    let near = test.spawn_enemy(EnemyType::Tank, Vec3::new(200.0, 0.5, 0.0));
+    let far = test.spawn_enemy(EnemyType::Tank, Vec3::new(0.0, 0.5, 900.0));
+
+    collect(&mut test, PowerUpType::Nuke, 1.0);
+    test.tick();
+
+    assert!(!test.exists(near));
+    assert!(test.exists(far));
+    assert!(test.all::<ActiveBuff>().is_empty(), "the nuke is instant");
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use void_survivor::{GameData, GameMode, GameState};
+use void_survivor::enemy::Enemy;
+use void_survivor::loot::LootType;
+use void_survivor::player::{Player, PlayerAim};
+use void_survivor::replay::{apply_buttons, capture_buttons, ClickInput, Replay, ReplayFrame, ReplayRecorder};
+use void_survivor::testing::TestApp;
+use void_survivor::shop::{ShopPurchaseEvent, SHOP_KEYS};
+use void_survivor::upgrade::{UpgradeChosenEvent, UpgradeHighlightEvent};
+use void_survivor::wave::WaveManager;
+
+// Where a run stands after a fixed stretch of play
+#[derive(Debug, PartialEq)]
+struct RunSnapshot {
+    score: u32,
+    wave: u32,
+    enemies_spawned: u32,
+    player_position: Vec3,
+    player_health: f32,
+    enemy_positions: Vec<Vec3>,
+}
+
+// Starts a new run and flies it with the same inputs every time
+fn play_run(test: &mut TestApp) -> (Replay, RunSnapshot) {
+    test.start_new_run();
+    test.resource_mut::<PlayerAim>().cursor_offset = Some(Vec2::new(150.0, 80.0));
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::W);
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::Space);
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::D);
+    test.advance_ticks(600);
+    test.resource_mut::<Input<KeyCode>>().release_all();
+    test.resource_mut::<PlayerAim>().cursor_offset = None;
+
+    let (player_position, player_health) = {
+        let mut query = test.app.world.query_filtered::<(&Transform, &Player), With<Player>>();
+        let (transform, player) = query.single(&test.app.world);
+        (transform.translation, player.health)
+    };
+    let mut enemy_positions: Vec<Vec3> = test.app.world
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(&test.app.world)
+        .map(|transform| transform.translation)
+        .collect();
+    enemy_positions.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.z.total_cmp(&b.z)));
+
+    let wave_manager = test.resource::<WaveManager>();
+    let snapshot = RunSnapshot {
+        score: test.resource::<GameData>().score,
+        wave: wave_manager.current_wave,
+        enemies_spawned: wave_manager.enemies_spawned,
+        player_position,
+        player_health,
+        enemy_positions,
+    };
+    // A run the ship didn't survive has already been saved
+    let recorder = test.resource::<ReplayRecorder>();
+    let replay = match &recorder.replay {
+        Some(replay) => replay.clone(),
+        None => Replay::load(recorder.last_saved.as_ref().expect("the run was recorded")).unwrap(),
+    };
+    (replay, snapshot)
+}
+
+#[test]
+fn every_run_starts_from_scratch() {
+    let directory = std::env::temp_dir().join(format!("void_survivor_replays_runs_{}", std::process::id()));
+    let mut test = TestApp::new();
+    test.resource_mut::<ReplayRecorder>().enabled = true;
+    test.resource_mut::<ReplayRecorder>().directory = directory.clone();
+
+    let (first_replay, first_run) = play_run(&mut test);
+    let (second_replay, second_run) = play_run(&mut test);
+    let _ = std::fs::remove_dir_all(&directory);
+
+    assert!(first_run.enemies_spawned > 0);
+    assert_eq!(second_run, first_run);
+    assert_eq!(second_replay, first_replay);
+}
+
+#[test]
+fn level_up_clicks_survive_a_save_and_load() {
+    let mut replay = Replay::new(7, 42, GameMode::Survival);
+    let frame = |buttons, aim, clicks| ReplayFrame { delta: Duration::from_millis(16), buttons, aim, clicks };
+    replay.frames.push(frame(0, None, Vec::new()));
+    replay.frames.push(frame(1 << 3, Some(Vec2::new(10.0, -5.0)), vec![ClickInput::Highlight(2), ClickInput::Choose(2)]));
+    replay.frames.push(frame(0, Some(Vec2::new(10.0, -5.0)), vec![ClickInput::Banish(0), ClickInput::Reroll, ClickInput::ShopPurchase(4)]));
+
+    assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+}
+
+#[test]
+fn gamepad_buttons_play_back_on_the_first_gamepad() {
+    let mut gamepad = Input::<GamepadButton>::default();
+    gamepad.press(GamepadButton::new(Gamepad::new(3), GamepadButtonType::South));
+    let buttons = capture_buttons(&Input::default(), &Input::default(), &gamepad);
+
+    let mut played_back = Input::<GamepadButton>::default();
+    apply_buttons(buttons, &mut Input::default(), &mut Input::default(), &mut played_back);
+
+    assert!(played_back.just_pressed(GamepadButton::new(Gamepad::new(0), GamepadButtonType::South)));
+    assert!(!played_back.pressed(GamepadButton::new(Gamepad::new(0), GamepadButtonType::North)));
+}
+
+#[test]
+fn level_up_clicks_are_recorded_on_their_frame() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<ReplayRecorder>().replay = Some(Replay::new(0, 0, GameMode::Waves));
+    test.spawn_loot(LootType::Experience, Vec3::ZERO, 100.0);
+    test.advance_ticks(2);
+    assert_eq!(*test.resource::<State<GameState>>().get(), GameState::LevelUp);
+
+    test.app.world.send_event(UpgradeHighlightEvent { index: 2 });
+    test.app.world.send_event(UpgradeChosenEvent { index: 2 });
+    test.tick();
+
+    let replay = test.resource::<ReplayRecorder>().replay.as_ref().unwrap();
+    let last_frame = replay.frames.last().unwrap();
+    assert_eq!(last_frame.clicks, vec![ClickInput::Highlight(2), ClickInput::Choose(2)]);
+    assert_eq!(replay.frames.iter().filter(|frame| !frame.clicks.is_empty()).count(), 1);
+}
+
+#[test]
+fn shop_clicks_are_recorded_but_hotkeys_stay_in_the_buttons() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<WaveManager>().enemies_to_spawn = 0;
+    test.advance_ticks(2);
+    test.resource_mut::<ReplayRecorder>().replay = Some(Replay::new(0, 0, GameMode::Waves));
+
+    test.app.world.send_event(ShopPurchaseEvent { slot: 3 });
+    test.tick();
+    test.resource_mut::<Input<KeyCode>>().press(SHOP_KEYS[4]);
+    test.tick();
+    test.resource_mut::<Input<KeyCode>>().reset(SHOP_KEYS[4]);
+
+    let replay = test.resource::<ReplayRecorder>().replay.as_ref().unwrap();
+    let clicks: Vec<ClickInput> = replay.frames.iter().flat_map(|frame| frame.clicks.clone()).collect();
+    assert_eq!(clicks, vec![ClickInput::ShopPurchase(3)]);
+}
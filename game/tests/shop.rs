@@ -0,0 +1,157 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use void_survivor::loot::LootType;
+use void_survivor::player::Player;
+use void_survivor::powerup::{ActiveBuff, PowerUpType};
+use void_survivor::shop::{Shop, ShopConfig, ShopItem, ShopPurchaseEvent, SHOP_KEYS};
+use void_survivor::testing::TestApp;
+use void_survivor::wave::WaveManager;
+
+const PLAYER_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+// Clears the held wave so the break, and the shop, start
+fn finish_wave(test: &mut TestApp) {
+    test.resource_mut::<WaveManager>().enemies_to_spawn = 0;
+    test.advance_ticks(2);
+}
+
+fn slot_of(test: &TestApp, matches: impl Fn(&ShopItem) -> bool) -> usize {
+    test.resource::<Shop>().offers.iter().position(|offer| matches(&offer.item)).unwrap()
+}
+
+fn buy(test: &mut TestApp, slot: usize) {
+    test.app.world.send_event(ShopPurchaseEvent { slot });
+    test.tick();
+}
+
+#[test]
+fn credits_pickup_adds_credits() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    test.spawn_loot(LootType::Credits, Vec3::ZERO, 12.0);
+
+    test.tick();
+
+    assert_eq!(test.get::<Player>(player).unwrap().credits, 12);
+}
+
+#[test]
+fn shop_opens_in_the_break_and_closes_with_the_next_wave() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+
+    finish_wave(&mut test);
+
+    let shop = test.resource::<Shop>();
+    assert!(shop.open);
+    assert_eq!(shop.offers.len(), 6);
+    assert!(shop.offers.iter().all(|offer| !offer.sold));
+
+    // Run out the break
+    let break_time = test.resource::<WaveManager>().break_time;
+    test.resource_mut::<WaveManager>().time_in_break = break_time;
+    test.advance_ticks(2);
+
+    assert!(!test.resource::<Shop>().open);
+}
+
+#[test]
+fn buying_spends_credits_and_sells_out() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    finish_wave(&mut test);
+    {
+        let mut player = test.get_mut::<Player>(player).unwrap();
+        player.health = 10.0;
+        player.credits = 100;
+    }
+    let repair = slot_of(&test, |item| matches!(item, ShopItem::Repair(_)));
+    let price = test.resource::<Shop>().offers[repair].price;
+
+    buy(&mut test, repair);
+    buy(&mut test, repair);
+
+    let player = test.get::<Player>(player).unwrap();
+    assert_eq!(player.credits, 100 - price);
+    assert_eq!(player.health, 10.0 + ShopConfig::default().repair_amount);
+    assert!(test.resource::<Shop>().offers[repair].sold);
+}
+
+#[test]
+fn bought_buff_starts_even_when_frames_pass_without_a_tick() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    finish_wave(&mut test);
+    test.get_mut::<Player>(player).unwrap().credits = 1000;
+    let buff = slot_of(&test, |item| matches!(item, ShopItem::Buff(_)));
+    let ShopItem::Buff(power_up) = test.resource::<Shop>().offers[buff].item.clone() else {
+        unreachable!()
+    };
+
+    // A fast frame rate: two frames go by before the next fixed tick
+    let timestep = test.resource::<Time<Fixed>>().timestep();
+    test.app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    test.app.world.send_event(ShopPurchaseEvent { slot: buff });
+    test.app.update();
+    test.app.update();
+    test.app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    test.advance_ticks(2);
+
+    let buffs: Vec<PowerUpType> = test.all::<ActiveBuff>().iter().map(|buff| buff.source).collect();
+    assert_eq!(buffs, vec![power_up]);
+}
+
+#[test]
+fn hotkeys_buy_their_slot() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    finish_wave(&mut test);
+    test.get_mut::<Player>(player).unwrap().credits = 1000;
+    let repair = slot_of(&test, |item| matches!(item, ShopItem::Repair(_)));
+
+    test.resource_mut::<Input<KeyCode>>().press(SHOP_KEYS[repair]);
+    test.tick();
+    test.resource_mut::<Input<KeyCode>>().reset(SHOP_KEYS[repair]);
+
+    assert!(test.resource::<Shop>().offers[repair].sold);
+}
+
+#[test]
+fn cannot_buy_without_enough_credits() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    finish_wave(&mut test);
+    test.get_mut::<Player>(player).unwrap().shields = 0.0;
+    let refill = slot_of(&test, |item| matches!(item, ShopItem::ShieldRefill));
+
+    buy(&mut test, refill);
+
+    assert!(test.get::<Player>(player).unwrap().shields < 1.0, "only regen, no refill");
+    assert!(!test.resource::<Shop>().offers[refill].sold);
+}
+
+#[test]
+fn rerolls_get_more_expensive() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    finish_wave(&mut test);
+    test.get_mut::<Player>(player).unwrap().credits = 1000;
+    let reroll = slot_of(&test, |item| matches!(item, ShopItem::Reroll));
+    let first_price = test.resource::<Shop>().offers[reroll].price;
+
+    buy(&mut test, reroll);
+
+    let shop = test.resource::<Shop>();
+    assert_eq!(shop.rerolls, 1);
+    assert!(shop.offers[reroll].price > first_price);
+    assert!(!shop.offers[reroll].sold);
+}
+
+#[test]
+fn prices_scale_with_the_wave() {
+    let config = ShopConfig::default();
+
+    assert_eq!(config.price(40, 1), 40);
+    assert!(config.price(40, 10) > config.price(40, 5));
+}
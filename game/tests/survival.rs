@@ -0,0 +1,109 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use void_survivor::{GameMode, GameState};
+use void_survivor::asteroid::Asteroid;
+use void_survivor::enemy::{Enemy, EnemyModifiers, EnemyType};
+use void_survivor::survival::{MiniBossEvent, SurvivalRun, SurvivalTimeline};
+use void_survivor::testing::TestApp;
+use void_survivor::ui::setup_death_screen;
+
+#[test]
+fn new_survival_run_starts_the_timeline_over() {
+    let mut test = TestApp::new();
+    test.app.insert_resource(GameMode::Survival);
+    test.start_new_run();
+    test.advance_ticks(600);
+    assert!(test.resource::<SurvivalRun>().elapsed > 9.0);
+    assert!(test.resource::<SurvivalRun>().enemies_spawned > 0);
+
+    // Lost, not won, so nothing marks the run as finished
+    test.resource_mut::<NextState<GameState>>().set(GameState::GameOver);
+    test.tick();
+    assert!(!test.resource::<SurvivalRun>().victory);
+    test.start_new_run();
+
+    let run = test.resource::<SurvivalRun>();
+    assert!(run.elapsed < 0.1, "{}", run.elapsed);
+    assert_eq!(run.next_mini_boss, 0);
+    assert!(run.enemies_spawned <= 1);
+}
+
+#[test]
+fn mini_bosses_do_not_spawn_inside_asteroids() {
+    let mut test = TestApp::new();
+    test.app.insert_resource(GameMode::Survival);
+    test.start_new_run();
+    test.clear_arena();
+
+    let spawn_radius = {
+        let mut timeline = test.resource_mut::<SurvivalTimeline>();
+        timeline.phases.clear();
+        timeline.mini_bosses = vec![MiniBossEvent {
+            time: 0.0,
+            enemy_type: EnemyType::Tank,
+            count: 1,
+            modifiers: EnemyModifiers::default(),
+        }];
+        timeline.spawn_radius
+    };
+    test.resource_mut::<SurvivalRun>().next_mini_boss = 0;
+
+    // Right where the lone boss would land, straight out along +X
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let asteroid_position = Vec3::new(spawn_radius, 0.0, 0.0);
+    test.app.world.spawn((Asteroid::new(100.0), TransformBundle::from_transform(Transform::from_translation(asteroid_position))));
+    test.tick();
+
+    let bosses: Vec<Vec3> = test.app.world
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(&test.app.world)
+        .map(|transform| transform.translation)
+        .collect();
+    assert_eq!(bosses.len(), 1);
+    let mut offset = bosses[0] - asteroid_position;
+    offset.y = 0.0;
+    assert!(offset.length() > 60.0, "{}", offset.length());
+}
+
+// The UI isn't part of the headless app, so build the screen by hand
+fn death_screen_text(test: &mut TestApp) -> Vec<String> {
+    test.app.world.run_system_once(setup_death_screen);
+    test.app.world
+        .query::<&Text>()
+        .iter(&test.app.world)
+        .flat_map(|text| text.sections.iter().map(|section| section.value.clone()))
+        .collect()
+}
+
+#[test]
+fn surviving_the_timeline_shows_a_victory_screen() {
+    let mut test = TestApp::new();
+    test.app.insert_resource(GameMode::Survival);
+    test.start_new_run();
+    test.clear_arena();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    test.resource_mut::<SurvivalTimeline>().duration = 61.0;
+    test.resource_mut::<SurvivalRun>().elapsed = 60.99;
+
+    test.advance_ticks(3);
+
+    assert!(test.resource::<SurvivalRun>().victory);
+    let text = death_screen_text(&mut test);
+    assert!(text.iter().any(|line| line == "VICTORY"), "{:?}", text);
+    assert!(text.iter().any(|line| line == "Survived 1:01"), "{:?}", text);
+    assert!(!text.iter().any(|line| line == "GAME OVER"), "{:?}", text);
+}
+
+#[test]
+fn dying_in_survival_is_still_game_over() {
+    let mut test = TestApp::new();
+    test.app.insert_resource(GameMode::Survival);
+    test.start_new_run();
+
+    test.resource_mut::<NextState<GameState>>().set(GameState::GameOver);
+    test.advance_ticks(2);
+
+    let text = death_screen_text(&mut test);
+    assert!(text.iter().any(|line| line == "GAME OVER"), "{:?}", text);
+    assert!(!text.iter().any(|line| line.starts_with("Survived")), "{:?}", text);
+}
@@ -0,0 +1,272 @@
+use bevy::prelude::*;
+use void_survivor::GameState;
+use void_survivor::enemy::EnemyType;
+use void_survivor::loot::{LootType, Rarity};
+use void_survivor::player::{Player, WeaponType, MAX_WEAPON_LEVEL};
+use void_survivor::rng::GameRng;
+use void_survivor::testing::TestApp;
+use void_survivor::upgrade::{
+    SpecialAbility, Upgrade, UpgradeCatalogue, UpgradeChosenEvent, UpgradeEntry, UpgradeHighlightEvent, UpgradeManager,
+    UpgradeType,
+};
+
+const PLAYER_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+fn state(test: &TestApp) -> GameState {
+    *test.resource::<State<GameState>>().get()
+}
+
+// Picks up enough experience for `levels` level-ups from level 1, then lets the state change land
+fn level_up(test: &mut TestApp, levels: u32) {
+    // One orb per level, as a single pickup only ever gives one level
+    for level in 1..=levels {
+        test.spawn_loot(LootType::Experience, Vec3::ZERO, level as f32 * 100.0);
+    }
+    test.advance_ticks(2);
+}
+
+fn press(test: &mut TestApp, key: KeyCode) {
+    let mut keys = test.resource_mut::<Input<KeyCode>>();
+    keys.clear();
+    keys.press(key);
+    test.tick();
+    test.resource_mut::<Input<KeyCode>>().reset(key);
+    test.tick();
+}
+
+#[test]
+fn level_up_pauses_on_three_offers() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+
+    level_up(&mut test, 1);
+
+    assert_eq!(state(&test), GameState::LevelUp);
+    assert_eq!(test.resource::<UpgradeManager>().level_up_offers.len(), 3);
+    assert_eq!(test.get::<Player>(player).unwrap().pending_level_ups, 1);
+}
+
+#[test]
+fn gameplay_is_held_on_the_level_up_screen() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+    let enemy = test.spawn_enemy(EnemyType::Chaser, Vec3::new(200.0, 0.5, 0.0));
+    test.tick();
+    let start = test.get::<Transform>(enemy).unwrap().translation;
+
+    test.advance_ticks(30);
+
+    assert_eq!(test.get::<Transform>(enemy).unwrap().translation, start);
+}
+
+#[test]
+fn number_key_applies_the_upgrade_and_resumes() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+    test.resource_mut::<UpgradeManager>().level_up_offers[1] = Upgrade::new(UpgradeType::HealthIncrease(25.0), 1);
+
+    press(&mut test, KeyCode::Key2);
+
+    assert_eq!(state(&test), GameState::Playing);
+    assert!(test.resource::<UpgradeManager>().level_up_offers.is_empty());
+    let player = test.get::<Player>(player).unwrap();
+    assert_eq!(player.max_health, 125.0);
+    assert_eq!(player.pending_level_ups, 0);
+}
+
+#[test]
+fn cursor_moves_and_enter_confirms() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+
+    // Wraps round from the first card to the last
+    press(&mut test, KeyCode::Left);
+    assert_eq!(test.resource::<UpgradeManager>().level_up_cursor, 2);
+    press(&mut test, KeyCode::Right);
+    press(&mut test, KeyCode::Right);
+    assert_eq!(test.resource::<UpgradeManager>().level_up_cursor, 1);
+    assert_eq!(state(&test), GameState::LevelUp);
+
+    press(&mut test, KeyCode::Return);
+
+    assert_eq!(state(&test), GameState::Playing);
+}
+
+#[test]
+fn clicked_card_is_chosen() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+
+    test.app.world.send_event(UpgradeChosenEvent { index: 0 });
+    test.advance_ticks(2);
+
+    assert_eq!(state(&test), GameState::Playing);
+    assert_eq!(test.get::<Player>(player).unwrap().pending_level_ups, 0);
+}
+
+#[test]
+fn hovered_card_takes_the_highlight() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+
+    test.app.world.send_event(UpgradeHighlightEvent { index: 2 });
+    test.tick();
+    assert_eq!(test.resource::<UpgradeManager>().level_up_cursor, 2);
+
+    press(&mut test, KeyCode::Return);
+
+    assert_eq!(state(&test), GameState::Playing);
+}
+
+#[test]
+fn each_pending_level_up_gets_its_own_pick() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 2);
+    assert_eq!(test.get::<Player>(player).unwrap().pending_level_ups, 2);
+
+    press(&mut test, KeyCode::Key1);
+    test.tick();
+
+    // Back on the screen straight away for the second one
+    assert_eq!(state(&test), GameState::LevelUp);
+    assert_eq!(test.get::<Player>(player).unwrap().pending_level_ups, 1);
+
+    press(&mut test, KeyCode::Key1);
+
+    assert_eq!(state(&test), GameState::Playing);
+    assert_eq!(test.get::<Player>(player).unwrap().pending_level_ups, 0);
+}
+
+fn entry(id: &str, upgrade_type: UpgradeType) -> UpgradeEntry {
+    UpgradeEntry {
+        id: id.to_string(),
+        upgrade_type,
+        weight: 1.0,
+        rarity: Rarity::Common,
+        max_stacks: None,
+        requires: Vec::new(),
+        excludes: Vec::new(),
+    }
+}
+
+fn catalogue(upgrades: Vec<UpgradeEntry>) -> UpgradeCatalogue {
+    UpgradeCatalogue { upgrades, ..UpgradeCatalogue::default() }
+}
+
+fn offered(manager: &UpgradeManager, catalogue: &UpgradeCatalogue, player: &Player) -> Vec<UpgradeType> {
+    manager
+        .generate_random_upgrades(catalogue, player, 10, &[], GameRng::new(7).upgrades())
+        .into_iter()
+        .map(|upgrade| upgrade.upgrade_type)
+        .collect()
+}
+
+#[test]
+fn offers_never_repeat_a_card() {
+    let catalogue = UpgradeCatalogue::default();
+    let manager = UpgradeManager::default();
+    let player = Player::new();
+
+    for seed in 0..50 {
+        let offers = manager.generate_random_upgrades(&catalogue, &player, 3, &[], GameRng::new(seed).upgrades());
+        assert_eq!(offers.len(), 3);
+        for (i, offer) in offers.iter().enumerate() {
+            assert!(offers[i + 1..].iter().all(|other| other.upgrade_type != offer.upgrade_type));
+        }
+    }
+}
+
+#[test]
+fn max_stacks_prerequisites_and_exclusions_limit_the_pool() {
+    let health = UpgradeType::HealthIncrease(25.0);
+    let shields = UpgradeType::ShieldIncrease(15.0);
+    let regen = UpgradeType::SpecialAbility(SpecialAbility::ShieldRegeneration);
+    let catalogue = catalogue(vec![
+        UpgradeEntry { max_stacks: Some(1), excludes: vec!["regen".to_string()], ..entry("health", health.clone()) },
+        entry("shields", shields.clone()),
+        UpgradeEntry { requires: vec!["shields".to_string()], ..entry("regen", regen.clone()) },
+    ]);
+    let mut manager = UpgradeManager::default();
+    let mut player = Player::new();
+
+    // Regen needs shields first
+    assert!(!offered(&manager, &catalogue, &player).contains(&regen));
+    manager.apply_upgrade(&Upgrade::new(shields.clone(), 1), &mut player);
+    assert!(offered(&manager, &catalogue, &player).contains(&regen));
+
+    // Health is one-off, and rules regen out once taken
+    manager.apply_upgrade(&Upgrade::new(health.clone(), 1), &mut player);
+    assert_eq!(offered(&manager, &catalogue, &player), vec![shields]);
+}
+
+#[test]
+fn maxed_weapons_are_not_offered() {
+    let catalogue = catalogue(vec![
+        entry("blaster", UpgradeType::WeaponUnlock(WeaponType::Blaster)),
+        entry("laser", UpgradeType::WeaponUnlock(WeaponType::Laser)),
+    ]);
+    let mut player = Player::new();
+    player.arsenal[0].level = MAX_WEAPON_LEVEL;
+
+    assert_eq!(
+        offered(&UpgradeManager::default(), &catalogue, &player),
+        vec![UpgradeType::WeaponUnlock(WeaponType::Laser)],
+    );
+}
+
+#[test]
+fn better_rarities_get_likelier_with_level() {
+    let catalogue = UpgradeCatalogue::default();
+    let share = |rarity: Rarity, level: u32| {
+        catalogue.rarity_weight(rarity, level) / catalogue.rarity_weight(Rarity::Common, level)
+    };
+
+    assert!(share(Rarity::Rare, 10) > share(Rarity::Rare, 1));
+    assert!(share(Rarity::Epic, 10) / share(Rarity::Epic, 1) > share(Rarity::Rare, 10) / share(Rarity::Rare, 1));
+}
+
+#[test]
+fn reroll_spends_a_charge_for_new_cards() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+    let charges = test.resource::<UpgradeCatalogue>().reroll_charges;
+
+    for _ in 0..charges + 1 {
+        press(&mut test, KeyCode::R);
+    }
+
+    let manager = test.resource::<UpgradeManager>();
+    assert_eq!(manager.rerolls_used, charges);
+    assert_eq!(manager.level_up_offers.len(), 3);
+    assert_eq!(state(&test), GameState::LevelUp);
+}
+
+#[test]
+fn banished_cards_never_come_back() {
+    let mut test = TestApp::new();
+    test.spawn_player(PLAYER_POSITION);
+    level_up(&mut test, 1);
+    let banished = test.resource::<UpgradeManager>().level_up_offers[0].upgrade_type.clone();
+
+    press(&mut test, KeyCode::B);
+
+    let manager = test.resource::<UpgradeManager>();
+    assert_eq!(manager.banishes_used, 1);
+    assert_eq!(manager.banished, vec![banished.clone()]);
+    assert_eq!(manager.level_up_offers.len(), 3);
+    assert!(manager.level_up_offers.iter().all(|offer| offer.upgrade_type != banished));
+
+    let catalogue = test.resource::<UpgradeCatalogue>().clone();
+    let player = Player::new();
+    for seed in 0..20 {
+        let offers = manager.generate_random_upgrades(&catalogue, &player, 3, &[], GameRng::new(seed).upgrades());
+        assert!(offers.iter().all(|offer| offer.upgrade_type != banished));
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use void_survivor::config::WaveConfig;
+use void_survivor::enemy::{Enemy, EnemyType};
+use void_survivor::testing::TestApp;
+use void_survivor::wave::{generate_procedural_wave, AIDirector, WaveCompleteEvent, WaveManager};
+
+#[test]
+fn wave_completes_once_last_enemy_dies() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    let first = test.spawn_enemy(EnemyType::Chaser, Vec3::new(300.0, 0.0, 0.0));
+    let second = test.spawn_enemy(EnemyType::Chaser, Vec3::new(-300.0, 0.0, 0.0));
+    {
+        let mut wave_manager = test.resource_mut::<WaveManager>();
+        wave_manager.enemies_spawned = 2;
+        wave_manager.enemies_to_spawn = 2;
+    }
+
+    test.get_mut::<Enemy>(first).unwrap().health = 0.0;
+    test.tick();
+    assert!(test.events::<WaveCompleteEvent>().is_empty());
+    assert!(test.resource::<WaveManager>().wave_active);
+
+    // The last enemy is despawned at the end of the tick it dies, the wave closes on the next
+    test.get_mut::<Enemy>(second).unwrap().health = 0.0;
+    test.tick();
+    assert!(test.events::<WaveCompleteEvent>().is_empty());
+    test.tick();
+    let completed = test.events::<WaveCompleteEvent>();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].wave_number, 1);
+    assert_eq!(completed[0].enemies_killed, 2);
+
+    let wave_manager = test.resource::<WaveManager>();
+    assert!(!wave_manager.wave_active);
+    assert!(wave_manager.last_reward.is_some());
+}
+
+#[test]
+fn wave_stays_open_while_spawns_are_pending() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+
+    // Nothing alive, but the held wave still has an enemy to come
+    test.advance_ticks(10);
+
+    assert!(test.resource::<WaveManager>().wave_active);
+}
+
+#[test]
+fn clear_time_is_real_time_whatever_the_director_pace() {
+    let mut test = TestApp::new();
+    test.spawn_player(Vec3::new(0.0, 0.5, 0.0));
+    {
+        let mut director = test.resource_mut::<AIDirector>();
+        director.enabled = true;
+        director.intensity = 1.5;
+    }
+
+    test.advance_ticks(119);
+    test.resource_mut::<WaveManager>().enemies_to_spawn = 1;
+    test.tick();
+
+    // Two seconds in, even though the script ran half again as fast
+    let completed = test.events::<WaveCompleteEvent>();
+    assert_eq!(completed.len(), 1);
+    assert!((completed[0].clear_time - 2.0).abs() < 0.01, "cleared in {}", completed[0].clear_time);
+    assert!(test.resource::<AIDirector>().last_clear_time.is_some_and(|time| (time - 2.0).abs() < 0.01));
+}
+
+#[test]
+fn procedural_waves_get_tougher_per_enemy() {
+    let config = WaveConfig::default();
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+    let early = generate_procedural_wave(21, &config, &mut rng);
+    let late = generate_procedural_wave(31, &config, &mut rng);
+
+    assert_eq!(early.modifiers.health_multiplier, config.difficulty_multiplier(21));
+    assert_eq!(early.modifiers.damage_multiplier, config.difficulty_multiplier(21));
+    assert!(late.modifiers.health_multiplier > early.modifiers.health_multiplier);
+    assert!(late.modifiers.damage_multiplier > early.modifiers.damage_multiplier);
+}
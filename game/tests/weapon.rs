@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use void_survivor::enemy::{Enemy, EnemyType};
+use void_survivor::loot::LootType;
+use void_survivor::player::{Player, PlayerAim, PlayerWeapon, Projectile, WeaponGainedEvent, WeaponType, MAX_WEAPON_LEVEL};
+use void_survivor::rng::GameRng;
+use void_survivor::testing::TestApp;
+use void_survivor::upgrade::{SpecialAbility, Upgrade, UpgradeCatalogue, UpgradeManager, UpgradeType};
+
+const PLAYER_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+fn weapon_level(test: &TestApp, player: Entity, weapon_type: WeaponType) -> Option<u32> {
+    test.get::<Player>(player)
+        .unwrap()
+        .arsenal
+        .iter()
+        .find(|weapon| weapon.weapon_type == weapon_type)
+        .map(|weapon| weapon.level)
+}
+
+#[test]
+fn weapon_crate_unlocks_a_new_weapon() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    // Blaster maxed out, so the crate has to pick something new
+    test.get_mut::<Player>(player).unwrap().arsenal[0].level = MAX_WEAPON_LEVEL;
+    test.spawn_loot(LootType::Weapon, Vec3::ZERO, 1.0);
+
+    test.tick();
+
+    assert_eq!(test.get::<Player>(player).unwrap().arsenal.len(), 2);
+    let gained = test.events::<WeaponGainedEvent>();
+    assert_eq!(gained.len(), 1);
+    assert_ne!(gained[0].weapon_type, WeaponType::Blaster);
+    assert_eq!(gained[0].level, 1);
+}
+
+#[test]
+fn weapon_crate_levels_up_an_owned_weapon() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    test.get_mut::<Player>(player).unwrap().arsenal = vec![
+        PlayerWeapon { level: 2, ..PlayerWeapon::new(WeaponType::Blaster) },
+        PlayerWeapon { level: MAX_WEAPON_LEVEL, ..PlayerWeapon::new(WeaponType::Laser) },
+        PlayerWeapon { level: MAX_WEAPON_LEVEL, ..PlayerWeapon::new(WeaponType::Rocket) },
+        PlayerWeapon { level: MAX_WEAPON_LEVEL, ..PlayerWeapon::new(WeaponType::AoePulse) },
+    ];
+    test.spawn_loot(LootType::Weapon, Vec3::ZERO, 1.0);
+
+    test.tick();
+
+    assert_eq!(weapon_level(&test, player, WeaponType::Blaster), Some(3));
+}
+
+#[test]
+fn maxed_weapons_stop_levelling() {
+    let mut player = Player::new();
+    for _ in 1..MAX_WEAPON_LEVEL {
+        assert!(player.add_weapon(WeaponType::Blaster).is_some());
+    }
+
+    assert_eq!(player.add_weapon(WeaponType::Blaster), None);
+    assert!(!player.weapon_crate_options().contains(&WeaponType::Blaster));
+}
+
+#[test]
+fn higher_levels_add_projectiles_and_behaviours() {
+    assert_eq!(WeaponType::Blaster.stats(1).projectiles, 1);
+    assert_eq!(WeaponType::Blaster.stats(3).projectiles, 2);
+    assert_eq!(WeaponType::Laser.stats(1).pierce, 0);
+    assert!(WeaponType::Laser.stats(3).pierce > 0);
+    assert_eq!(WeaponType::Rocket.stats(1).splash_radius, 0.0);
+    assert!(WeaponType::Rocket.stats(3).splash_radius > 0.0);
+    assert!(WeaponType::AoePulse.stats(5).damage > WeaponType::AoePulse.stats(1).damage);
+}
+
+#[test]
+fn every_owned_weapon_fires() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    {
+        let mut player = test.get_mut::<Player>(player).unwrap();
+        player.add_weapon(WeaponType::AoePulse);
+        player.damage_multiplier = 1.5;
+    }
+    test.resource_mut::<PlayerAim>().cursor_offset = Some(Vec2::new(100.0, 0.0));
+    test.resource_mut::<Input<KeyCode>>().press(KeyCode::Space);
+
+    // Long enough for the slower pulse to come off cooldown
+    test.advance_ticks(35);
+
+    let arsenal = &test.get::<Player>(player).unwrap().arsenal;
+    assert!(arsenal.iter().all(|weapon| weapon.last_shot > 0.0));
+    let pulse_shots = test.all::<Projectile>()
+        .into_iter()
+        .filter(|projectile| projectile.damage == 12.0 * 1.5)
+        .count();
+    assert_eq!(pulse_shots, WeaponType::AoePulse.stats(1).projectiles as usize);
+}
+
+#[test]
+fn piercing_shot_passes_through_enemies() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(-300.0, 0.5, 0.0));
+    let first = test.spawn_enemy(EnemyType::Tank, Vec3::new(100.0, 0.0, 0.0));
+    let second = test.spawn_enemy(EnemyType::Tank, Vec3::new(160.0, 0.0, 0.0));
+    let projectile = test.spawn_projectile(player, Vec3::new(90.0, 0.0, 0.0), Vec3::X, 40.0);
+    test.get_mut::<Projectile>(projectile).unwrap().pierce = 1;
+
+    test.advance_ticks(10);
+
+    assert_eq!(test.get::<Enemy>(first).unwrap().health, 110.0);
+    assert_eq!(test.get::<Enemy>(second).unwrap().health, 110.0);
+    assert!(!test.exists(projectile));
+}
+
+#[test]
+fn explosive_shot_splashes_nearby_enemies() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(Vec3::new(-300.0, 0.5, 0.0));
+    let target = test.spawn_enemy(EnemyType::Tank, Vec3::new(100.0, 0.0, 0.0));
+    let bystander = test.spawn_enemy(EnemyType::Tank, Vec3::new(100.0, 0.0, 40.0));
+    let outside = test.spawn_enemy(EnemyType::Tank, Vec3::new(100.0, 0.0, 200.0));
+    let projectile = test.spawn_projectile(player, Vec3::new(90.0, 0.0, 0.0), Vec3::X, 40.0);
+    test.get_mut::<Projectile>(projectile).unwrap().splash_radius = 60.0;
+
+    test.tick();
+
+    assert_eq!(test.get::<Enemy>(target).unwrap().health, 110.0);
+    assert_eq!(test.get::<Enemy>(bystander).unwrap().health, 130.0);
+    assert_eq!(test.get::<Enemy>(outside).unwrap().health, 150.0);
+}
+
+fn laser_ready_to_evolve(player: &mut Player, manager: &mut UpgradeManager) {
+    player.arsenal.push(PlayerWeapon { level: MAX_WEAPON_LEVEL, ..PlayerWeapon::new(WeaponType::Laser) });
+    manager.apply_upgrade(&Upgrade::new(UpgradeType::SpecialAbility(SpecialAbility::PiercingShots), 1), player);
+}
+
+#[test]
+fn evolution_needs_a_maxed_weapon_and_its_passive() {
+    let catalogue = UpgradeCatalogue::default();
+    let mut manager = UpgradeManager::default();
+    let mut player = Player::new();
+
+    // Passive without the weapon maxed
+    manager.apply_upgrade(&Upgrade::new(UpgradeType::SpecialAbility(SpecialAbility::PiercingShots), 1), &mut player);
+    player.add_weapon(WeaponType::Laser);
+    assert!(manager.ready_evolutions(&catalogue, &player).is_empty());
+
+    // Maxed weapon without the passive
+    let mut player = Player::new();
+    player.arsenal.push(PlayerWeapon { level: MAX_WEAPON_LEVEL, ..PlayerWeapon::new(WeaponType::Rocket) });
+    assert!(manager.ready_evolutions(&catalogue, &player).is_empty());
+
+    laser_ready_to_evolve(&mut player, &mut manager);
+    let ready = manager.ready_evolutions(&catalogue, &player);
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].evolves_into, WeaponType::PrismBeam);
+}
+
+#[test]
+fn ready_evolution_is_the_first_card_offered() {
+    let catalogue = UpgradeCatalogue::default();
+    let mut manager = UpgradeManager::default();
+    let mut player = Player::new();
+    laser_ready_to_evolve(&mut player, &mut manager);
+
+    let offers = manager.generate_random_upgrades(&catalogue, &player, 3, &[], GameRng::new(1).upgrades());
+
+    assert_eq!(offers.len(), 3);
+    let evolution = UpgradeType::EvolveWeapon { from: WeaponType::Laser, into: WeaponType::PrismBeam };
+    assert_eq!(offers[0].upgrade_type, evolution);
+
+    // Taking it swaps the Laser out for good
+    manager.apply_upgrade(&offers[0], &mut player);
+    assert!(player.arsenal.iter().any(|weapon| weapon.weapon_type == WeaponType::PrismBeam));
+    assert!(player.arsenal.iter().all(|weapon| weapon.weapon_type != WeaponType::Laser));
+    assert!(!player.weapon_crate_options().contains(&WeaponType::Laser));
+    assert_eq!(player.add_weapon(WeaponType::Laser), None);
+    assert!(manager.ready_evolutions(&catalogue, &player).is_empty());
+}
+
+#[test]
+fn weapon_crate_evolves_a_ready_weapon() {
+    let mut test = TestApp::new();
+    let player = test.spawn_player(PLAYER_POSITION);
+    {
+        let mut manager = UpgradeManager::default();
+        laser_ready_to_evolve(&mut test.get_mut::<Player>(player).unwrap(), &mut manager);
+        *test.resource_mut::<UpgradeManager>() = manager;
+    }
+    test.spawn_loot(LootType::Weapon, Vec3::ZERO, 1.0);
+
+    test.tick();
+
+    assert_eq!(weapon_level(&test, player, WeaponType::PrismBeam), Some(MAX_WEAPON_LEVEL));
+    assert_eq!(weapon_level(&test, player, WeaponType::Laser), None);
+    let gained = test.events::<WeaponGainedEvent>();
+    assert_eq!(gained.len(), 1);
+    assert_eq!(gained[0].weapon_type, WeaponType::PrismBeam);
+    assert_eq!(gained[0].level, MAX_WEAPON_LEVEL);
+
+    // Recorded like a level-up pick, so it isn't offered again anywhere
+    let evolution = UpgradeType::EvolveWeapon { from: WeaponType::Laser, into: WeaponType::PrismBeam };
+    assert_eq!(test.resource::<UpgradeManager>().times_taken(&evolution), 1);
+}